@@ -1,3 +1,7 @@
 fn main() {
-    tauri_build::build()
+    // Declaring the commands gives each an allow- and deny- permission,
+    // which is what lets capabilities/table.json leave most of them out.
+    let manifest = tauri_build::AppManifest::new().commands(tauri_command_types::COMMAND_NAMES);
+    tauri_build::try_build(tauri_build::Attributes::new().app_manifest(manifest))
+        .expect("failed to run tauri-build");
 }
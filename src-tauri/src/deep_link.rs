@@ -0,0 +1,151 @@
+use {
+    std::sync::Mutex,
+    tauri::{App, AppHandle, Emitter, EventTarget, Url},
+    tauri_command_types::{DEEP_LINK_EVENT, LOBBY},
+    tauri_plugin_deep_link::DeepLinkExt,
+};
+
+const SCHEME: &str = "spa";
+
+/// The path of the link the app was launched with, until the lobby
+/// asks for it.
+#[derive(Default)]
+pub(crate) struct Pending(Mutex<Option<String>>);
+
+impl Pending {
+    pub(crate) fn take(&self) -> Option<String> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// The path within the app that `url` refers to, e.g. both
+/// spa://table/12 and https://host/table/12 are /table/12.  The query
+/// and fragment are dropped.  `None` if `url` isn't a link to the app.
+fn path(url: &Url) -> Option<String> {
+    let path = match url.scheme() {
+        // The "table" in spa://table/12 is the host
+        SCHEME => format!(
+            "/{}{}",
+            url.host_str().filter(|host| !host.is_empty())?,
+            url.path()
+        ),
+        // App Links and Universal Links
+        "http" | "https" => url.path().to_string(),
+        _ => return None,
+    };
+    Some(match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    })
+}
+
+fn link_path(url: &Url) -> Option<String> {
+    let path = path(url);
+    if path.is_none() {
+        log::warn!("Ignoring link {url}");
+    }
+    path
+}
+
+/// Remembers the link, if any, that launched the app, and passes along
+/// the ones that are opened from now on.
+pub(crate) fn setup(app: &App) -> Pending {
+    let deep_link = app.deep_link();
+
+    // Installed bundles register the scheme themselves, but on these
+    // platforms `cargo tauri dev` needs to do it at runtime.
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = deep_link.register_all() {
+        log::error!("Could not register deep links: {e}");
+    }
+
+    let launched_with = match deep_link.get_current() {
+        Err(e) => {
+            log::error!("Could not get launch link: {e}");
+            None
+        }
+        Ok(urls) => urls.and_then(|urls| urls.first().and_then(link_path)),
+    };
+
+    let app_handle = app.handle().clone();
+    deep_link.on_open_url(move |event| {
+        for path in event.urls().iter().filter_map(link_path) {
+            forward(&app_handle, path);
+        }
+    });
+
+    Pending(Mutex::new(launched_with))
+}
+
+fn forward(app_handle: &AppHandle, path: String) {
+    let lobby = EventTarget::Webview {
+        label: LOBBY.to_string(),
+    };
+    if let Err(e) = app_handle.emit_to(lobby, DEEP_LINK_EVENT, path) {
+        log::error!("Could not emit {DEEP_LINK_EVENT}: {e:?}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(url: &str) -> Option<String> {
+        path(&url.parse().unwrap())
+    }
+
+    #[test]
+    fn custom_scheme() {
+        assert_eq!(link("spa://table/12").as_deref(), Some("/table/12"));
+        assert_eq!(link("spa://index").as_deref(), Some("/index"));
+    }
+
+    #[test]
+    fn app_links() {
+        assert_eq!(
+            link("https://example.com/table/12").as_deref(),
+            Some("/table/12")
+        );
+        assert_eq!(
+            link("http://example.com/table/12").as_deref(),
+            Some("/table/12")
+        );
+        assert_eq!(link("https://example.com").as_deref(), Some("/"));
+    }
+
+    #[test]
+    fn trims_trailing_slashes() {
+        assert_eq!(link("spa://table/12/").as_deref(), Some("/table/12"));
+        assert_eq!(
+            link("https://example.com/table/12//").as_deref(),
+            Some("/table/12")
+        );
+        assert_eq!(link("https://example.com/").as_deref(), Some("/"));
+    }
+
+    #[test]
+    fn drops_query_and_fragment() {
+        assert_eq!(
+            link("spa://table/12?seat=2#chat").as_deref(),
+            Some("/table/12")
+        );
+        assert_eq!(
+            link("https://example.com/table/12/?seat=2").as_deref(),
+            Some("/table/12")
+        );
+        assert_eq!(link("https://example.com/?x=1").as_deref(), Some("/"));
+    }
+
+    #[test]
+    fn rejects_other_links() {
+        for url in [
+            "file:///table/12",
+            "mailto:someone@example.com",
+            "tauri://localhost/table/12",
+            "spa:table/12",
+            "spa:///table/12",
+        ] {
+            assert_eq!(link(url), None, "{url}");
+        }
+    }
+}
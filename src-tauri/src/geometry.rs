@@ -0,0 +1,122 @@
+use {
+    std::{collections::HashMap, fs, path::PathBuf, sync::Mutex},
+    tauri::{AppHandle, Manager, WebviewWindow, WindowEvent},
+    tauri_command_types::{Geometry, Position, Size},
+};
+
+const FILE_NAME: &str = "window-geometry.json";
+
+/// The geometry of every window we've seen, by label, as loaded from
+/// and saved to `FILE_NAME` in the app data dir.
+#[derive(Default)]
+pub(crate) struct Geometries(Mutex<HashMap<String, Geometry>>);
+
+fn path(app_handle: &AppHandle) -> Option<PathBuf> {
+    match app_handle.path().app_data_dir() {
+        Err(e) => {
+            log::error!("No app data dir: {e:?}");
+            None
+        }
+        Ok(dir) => Some(dir.join(FILE_NAME)),
+    }
+}
+
+impl Geometries {
+    pub(crate) fn load(app_handle: &AppHandle) -> Self {
+        let Some(path) = path(app_handle) else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path) {
+            // Not having saved anything yet is normal
+            Err(_) => Self::default(),
+            Ok(json) => match serde_json::from_str(&json) {
+                Err(e) => {
+                    log::error!("Could not parse {path:?}: {e}");
+                    Self::default()
+                }
+                Ok(geometries) => Self(Mutex::new(geometries)),
+            },
+        }
+    }
+
+    pub(crate) fn get(&self, label: &str) -> Option<Geometry> {
+        self.0.lock().unwrap().get(label).copied()
+    }
+
+    fn update(&self, label: &str, f: impl FnOnce(&mut Geometry)) {
+        f(self.0.lock().unwrap().entry(label.to_string()).or_default())
+    }
+
+    pub(crate) fn save(&self, app_handle: &AppHandle) {
+        let Some(path) = path(app_handle) else {
+            return;
+        };
+        let json = match serde_json::to_string_pretty(&*self.0.lock().unwrap()) {
+            Err(e) => {
+                log::error!("Could not serialize geometries: {e}");
+                return;
+            }
+            Ok(json) => json,
+        };
+        if let Some(dir) = path.parent()
+            && let Err(e) = fs::create_dir_all(dir)
+        {
+            log::error!("Could not create {dir:?}: {e}");
+            return;
+        }
+        if let Err(e) = fs::write(&path, json) {
+            log::error!("Could not write {path:?}: {e}");
+        }
+    }
+}
+
+/// Moves and resizes `window` to wherever it was last time.
+pub(crate) fn restore(window: &WebviewWindow) {
+    let Some(Geometry { size, position }) = window.state::<Geometries>().get(window.label()) else {
+        return;
+    };
+    if let Some(Size { width, height }) = size {
+        let _ = window.set_size(tauri::LogicalSize::new(width, height));
+    }
+    if let Some(Position { top, left }) = position {
+        let _ = window.set_position(tauri::LogicalPosition::new(left, top));
+    }
+}
+
+/// Keeps `window`'s entry in `Geometries` up to date, and writes them
+/// all out when it goes away.
+pub(crate) fn track(window: &WebviewWindow) {
+    let app_handle = window.app_handle().clone();
+    let label = window.label().to_string();
+    let w = window.clone();
+
+    window.on_window_event(move |e| {
+        // Asked each time, since the window may have moved to a
+        // monitor with a different scale factor.
+        let scale_factor = w.scale_factor().unwrap_or(1.0);
+        let geometries = app_handle.state::<Geometries>();
+        match e {
+            WindowEvent::Moved(position) => {
+                let position = position.to_logical::<i32>(scale_factor);
+                geometries.update(&label, |g| {
+                    g.position = Some(Position {
+                        top: position.y,
+                        left: position.x,
+                    })
+                });
+            }
+            // Minimizing reports a zero size on some platforms
+            WindowEvent::Resized(size) if size.width > 0 && size.height > 0 => {
+                let size = size.to_logical::<u32>(scale_factor);
+                geometries.update(&label, |g| {
+                    g.size = Some(Size {
+                        height: size.height,
+                        width: size.width,
+                    })
+                });
+            }
+            WindowEvent::Destroyed => geometries.save(&app_handle),
+            _ => {}
+        }
+    });
+}
@@ -1,43 +1,65 @@
+mod deep_link;
+#[cfg(desktop)]
+mod geometry;
+#[cfg(desktop)]
+mod menu;
+mod registry;
+#[cfg(desktop)]
+mod tray;
+mod webview_url;
+
 use {
     tauri::{Emitter, EventTarget, Manager, WebviewWindow, WindowEvent},
-    tauri_command_types::{CLOSED_EVENT, CloseNotification, Position, Size},
+    tauri_command_types::{
+        Close, CommandError, Focus, LIFECYCLE_EVENT, LOBBY, LifecycleNotification, ListWindows,
+        PopUpFeatures, Response, SetMenuTables, SetTitle, TableId, TakeDeepLink,
+        WindowLifecycleEvent,
+    },
 };
 
-#[tauri::command(rename_all = "snake_case")]
-async fn open_window(
-    app_handle: tauri::AppHandle,
-    url: String,
-    target: String,
-
-    #[cfg_attr(mobile, expect(unused_variables))] size: Option<Size>,
-
-    #[cfg_attr(mobile, expect(unused_variables))] position: Option<Position>,
+#[tauri::command]
+async fn open_window(app_handle: tauri::AppHandle, args: PopUpFeatures) -> Response<PopUpFeatures> {
+    #[cfg_attr(mobile, expect(unused_variables))]
+    let PopUpFeatures {
+        url,
+        target,
+        id,
+        size,
+        position,
+        restore_geometry,
+        lifecycle,
+    } = args;
 
-    close_notification: Option<CloseNotification>,
-) -> Result<bool, String> {
-    use tauri::WebviewUrl::*;
+    // Opening a table that's already open just brings it forward.
+    if app_handle.get_webview_window(&target).is_some() {
+        return focus_window(app_handle, Focus { label: target }).await;
+    }
 
-    let url = if cfg!(debug_assertions) {
-        External(url.parse().map_err(|e| format!("Invalid url {url}: {e}"))?)
+    // Only `cargo tauri dev` serves pages from the dev server
+    let dev_url = if cfg!(debug_assertions) {
+        app_handle.config().build.dev_url.clone()
     } else {
-        // The old code looked explicitly for the prefix
-        // tauri://localhost/ but under Windows, we get
-        // http://tauri.localhost/ so now we simply look for the third
-        // slash.
-        let url = url
-            .match_indices('/')
-            .nth(2)
-            .map(|(n, _)| &url[(n + 1)..])
-            .ok_or_else(|| format!("Expected three slashes url: {url}"))?;
-
-        App(std::path::PathBuf::from(url))
+        None
     };
+    let url = webview_url::parse(&url, dev_url.as_ref())?;
+
+    // Unless the lobby is placing windows itself, wherever the user
+    // last left this window wins over what was asked for.
+    #[cfg(desktop)]
+    let saved = restore_geometry
+        .then(|| app_handle.state::<geometry::Geometries>().get(&target))
+        .flatten();
 
     #[cfg_attr(mobile, expect(unused_mut))]
     let mut builder = tauri::WebviewWindowBuilder::new(&app_handle, target, url);
 
     #[cfg(desktop)]
     {
+        use tauri_command_types::{Position, Size};
+
+        let size = saved.and_then(|g| g.size).or(size);
+        let position = saved.and_then(|g| g.position).or(position);
+
         builder = builder.resizable(true);
         if let Some(Size { width, height }) = size {
             builder = builder.inner_size(width.into(), height.into());
@@ -48,90 +70,294 @@ async fn open_window(
     }
     let window = builder
         .build()
-        .map_err(|e| format!("Could not build window: {e}"))?;
+        .map_err(|e| CommandError::BuildFailed(e.to_string()))?;
 
-    if let Some(CloseNotification { receiver_label, id }) = close_notification {
-        let target = EventTarget::Webview {
-            label: receiver_label,
-        };
+    registry::register(&window, id);
+    #[cfg(desktop)]
+    {
+        geometry::track(&window);
+        forward_window_events(&window);
+    }
 
-        window.on_window_event(move |e| {
-            if matches!(e, &WindowEvent::Destroyed)
-                && let Err(err) = app_handle.emit_to(target.clone(), CLOSED_EVENT, id)
-            {
-                log::error!("Could not send close: {err:?}");
-            }
-        });
+    if let Some(lifecycle) = lifecycle {
+        emit_lifecycle(&window, id, lifecycle);
     }
 
     #[cfg(desktop)]
     let _ = window.set_focus(); // Perhaps we want to log failure
 
-    Ok(true)
+    Ok(())
 }
 
+/// Emits `LIFECYCLE_EVENT`s about table `id`'s window to the webview
+/// labeled `receiver_label`, starting with `Created`.
+fn emit_lifecycle(
+    window: &WebviewWindow,
+    id: TableId,
+    LifecycleNotification { receiver_label }: LifecycleNotification,
+) {
+    use WindowLifecycleEvent::*;
+
+    let app_handle = window.app_handle().clone();
+    let target = EventTarget::Webview {
+        label: receiver_label,
+    };
+    let emit = move |event: WindowLifecycleEvent| {
+        if let Err(err) = app_handle.emit_to(target.clone(), LIFECYCLE_EVENT, event) {
+            log::error!("Could not emit {event:?}: {err:?}");
+        }
+    };
+
+    emit(Created(id));
+    window.on_window_event(move |e| {
+        let event = match e {
+            WindowEvent::Focused(true) => Focused(id),
+            WindowEvent::CloseRequested { .. } => CloseRequested(id),
+            WindowEvent::Destroyed => Closed(id),
+            _ => return,
+        };
+        emit(event);
+    });
+}
+
+/// Emits `WINDOW_EVENT` for whatever happens to `window` that the
+/// frontend's `Window::events` cares about.
+#[cfg(desktop)]
+fn forward_window_events(window: &WebviewWindow) {
+    use {
+        std::sync::atomic::{AtomicBool, Ordering},
+        tauri_command_types::{
+            LabeledWindowEvent, Position, Size, WINDOW_EVENT, WindowEvent as Event,
+        },
+    };
+
+    let app_handle = window.app_handle().clone();
+    let w = window.clone();
+    let minimized = AtomicBool::new(false);
+
+    window.on_window_event(move |e| {
+        let scale_factor = w.scale_factor().unwrap_or(1.0);
+        let event = match e {
+            WindowEvent::Moved(position) => {
+                let position = position.to_logical::<i32>(scale_factor);
+                Event::Moved(Position {
+                    top: position.y,
+                    left: position.x,
+                })
+            }
+            // There's no minimize event, so we look for it whenever the
+            // size changes.
+            WindowEvent::Resized(size) => {
+                let is_minimized = w.is_minimized().unwrap_or(false);
+                if minimized.swap(is_minimized, Ordering::Relaxed) != is_minimized {
+                    if is_minimized {
+                        Event::Minimized
+                    } else {
+                        Event::Restored
+                    }
+                } else if is_minimized {
+                    return;
+                } else {
+                    let size = size.to_logical::<u32>(scale_factor);
+                    Event::Resized(Size {
+                        height: size.height,
+                        width: size.width,
+                    })
+                }
+            }
+            WindowEvent::Focused(true) => Event::Focused,
+            WindowEvent::Focused(false) => Event::Blurred,
+            _ => return,
+        };
+        let payload = LabeledWindowEvent {
+            label: w.label().to_string(),
+            event,
+        };
+        if let Err(err) = app_handle.emit(WINDOW_EVENT, payload) {
+            log::error!("Could not emit {WINDOW_EVENT}: {err:?}");
+        }
+    });
+}
+
+#[cfg(desktop)]
 fn window_apply(
     app_handle: tauri::AppHandle,
     label: &str,
-    f: impl FnOnce(WebviewWindow) -> Result<bool, String>,
-) -> Result<bool, String> {
-    app_handle
+    f: impl FnOnce(WebviewWindow) -> tauri::Result<()>,
+) -> Result<(), CommandError> {
+    let window = app_handle
         .get_webview_window(label)
-        .ok_or_else(|| format!("Could not get window {label}"))
-        .and_then(f)
+        .ok_or_else(|| CommandError::WindowNotFound(label.to_string()))?;
+    f(window).map_err(failed(label))
+}
+
+fn failed(label: &str) -> impl FnOnce(tauri::Error) -> CommandError {
+    move |e| CommandError::Failed {
+        label: label.to_string(),
+        reason: e.to_string(),
+    }
 }
 
 #[tauri::command]
 async fn set_title(
     app_handle: tauri::AppHandle,
-    label: String,
-    #[cfg_attr(mobile, expect(unused_variables))] title: String,
-) -> Result<bool, String> {
-    #[cfg_attr(mobile, expect(unused_variables))]
-    window_apply(app_handle, &label, |w| {
-        #[cfg(desktop)]
-        {
-            w.set_title(&title)
-                .map_err(|e| format!("Could not set window {label} title to {title}: {e:?}"))
-                .map(|_| true)
-        }
-        #[cfg(mobile)]
-        {
-            Ok(false)
-        }
-    })
+    webview: tauri::Webview,
+    args: SetTitle,
+) -> Response<SetTitle> {
+    // capabilities/table.json lets tables call this at all, but only
+    // about themselves.
+    let caller = webview.label();
+    if caller != LOBBY && caller != args.label {
+        return Err(CommandError::Forbidden {
+            caller: caller.to_string(),
+            label: args.label,
+        });
+    }
+
+    app_handle
+        .state::<registry::Registry>()
+        .set_title(&app_handle, &args.label, &args.title);
+
+    #[cfg(desktop)]
+    {
+        let SetTitle { label, title } = args;
+        window_apply(app_handle, &label, |w| w.set_title(&title))
+    }
+    // On mobile, tables are routes in the lobby's page, so it's up to
+    // the lobby.
+    #[cfg(mobile)]
+    {
+        app_handle
+            .emit_to(lobby(), tauri_command_types::SET_TITLE_EVENT, &args)
+            .map_err(failed(&args.label))
+    }
 }
 
 #[tauri::command]
-async fn close_window(app_handle: tauri::AppHandle, label: String) -> Result<bool, String> {
-    #[cfg_attr(mobile, expect(unused_variables))]
-    window_apply(app_handle, &label, |w| {
-        #[cfg(desktop)]
-        {
-            w.close()
-                .map_err(|e| format!("Could not close window {label}: {e:?}"))
-                .map(|_| true)
-        }
-        #[cfg(mobile)]
-        {
-            Ok(false)
-        }
-    })
+async fn close_window(app_handle: tauri::AppHandle, args: Close) -> Response<Close> {
+    #[cfg(desktop)]
+    {
+        window_apply(app_handle, &args.label, |w| w.close())
+    }
+    #[cfg(mobile)]
+    {
+        app_handle
+            .emit_to(lobby(), tauri_command_types::CLOSE_WINDOW_EVENT, &args)
+            .map_err(failed(&args.label))
+    }
+}
+
+/// Where mobile sends what only the single-page lobby can do.
+#[cfg(mobile)]
+fn lobby() -> EventTarget {
+    EventTarget::Webview {
+        label: LOBBY.to_string(),
+    }
+}
+
+#[tauri::command]
+async fn focus_window(
+    #[cfg_attr(mobile, expect(unused_variables))] app_handle: tauri::AppHandle,
+    #[cfg_attr(mobile, expect(unused_variables))] args: Focus,
+) -> Response<Focus> {
+    #[cfg(desktop)]
+    {
+        window_apply(app_handle, &args.label, |w| {
+            if w.is_minimized().unwrap_or(false) {
+                let _ = w.unminimize(); // Perhaps we want to log failure
+            }
+            w.set_focus()
+        })
+    }
+    #[cfg(mobile)]
+    {
+        Err(CommandError::Unsupported)
+    }
+}
+
+#[tauri::command]
+async fn take_deep_link(
+    pending: tauri::State<'_, deep_link::Pending>,
+    args: TakeDeepLink,
+) -> Response<TakeDeepLink> {
+    let TakeDeepLink = args;
+    Ok(pending.take())
+}
+
+#[tauri::command]
+async fn set_menu_tables(
+    #[cfg_attr(mobile, expect(unused_variables))] app_handle: tauri::AppHandle,
+    #[cfg_attr(mobile, expect(unused_variables))] args: SetMenuTables,
+) -> Response<SetMenuTables> {
+    #[cfg(desktop)]
+    {
+        menu::set_tables(&app_handle, &args.tables)
+            .map_err(|e| CommandError::MenuFailed(e.to_string()))
+    }
+    #[cfg(mobile)]
+    {
+        Err(CommandError::Unsupported)
+    }
+}
+
+#[tauri::command]
+async fn list_windows(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, registry::Registry>,
+    args: ListWindows,
+) -> Response<ListWindows> {
+    let ListWindows = args;
+    Ok(registry.list(&app_handle))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    // Has to be the first plugin
+    #[cfg(desktop)]
+    let builder = builder
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(main) = app.get_webview_window(LOBBY) {
+                let _ = main.set_focus(); // Perhaps we want to log failure
+            }
+        }))
+        .menu(|app_handle| menu::build(app_handle, &[]))
+        .on_menu_event(menu::handle);
+
+    builder
         .plugin(
             tauri_plugin_log::Builder::new()
                 .level(log::LevelFilter::Info)
                 .build(),
         )
-        .invoke_handler(tauri::generate_handler![
-            open_window,
-            set_title,
-            close_window
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .plugin(tauri_plugin_deep_link::init())
+        .setup(|app| {
+            app.manage(deep_link::setup(app));
+            app.manage(registry::Registry::default());
+            #[cfg(desktop)]
+            {
+                app.manage(geometry::Geometries::load(app.handle()));
+                tray::create(app.handle())?;
+                if let Some(main) = app.get_webview_window(LOBBY) {
+                    geometry::restore(&main);
+                    geometry::track(&main);
+                }
+            }
+            Ok(())
+        })
+        .invoke_handler(tauri_command_types::generate_handler!())
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(
+            |#[cfg_attr(mobile, expect(unused_variables))] app_handle,
+             #[cfg_attr(mobile, expect(unused_variables))] event| {
+                // Quitting from the menu or tray doesn't destroy the
+                // windows first, so their geometry is saved here.
+                #[cfg(desktop)]
+                if let tauri::RunEvent::Exit = event {
+                    app_handle.state::<geometry::Geometries>().save(app_handle);
+                }
+            },
+        );
 }
@@ -0,0 +1,133 @@
+use {
+    crate::registry::Registry,
+    tauri::{
+        AppHandle, Emitter, EventTarget, Manager, WebviewWindow,
+        menu::{Menu, MenuBuilder, MenuEvent, MenuItemBuilder, SubmenuBuilder},
+    },
+    tauri_command_types::{LOBBY, MENU_EVENT, MenuAction, MenuTable, TableId},
+};
+
+// The tray menu uses these ids too, so that `handle` serves both.
+pub(crate) const NEW_TABLE: &str = "new-table";
+const CLOSE_TABLE: &str = "close-table";
+pub(crate) const FOCUS_LOBBY: &str = "focus-lobby";
+pub(crate) const QUIT: &str = "quit";
+const FOCUS_TABLE_PREFIX: &str = "focus-table:";
+
+pub(crate) fn focus_table_id(id: TableId) -> String {
+    format!("{FOCUS_TABLE_PREFIX}{id}")
+}
+
+pub(crate) fn build(
+    app_handle: &AppHandle,
+    tables: &[MenuTable],
+) -> tauri::Result<Menu<tauri::Wry>> {
+    let new_table = MenuItemBuilder::with_id(NEW_TABLE, "New Table")
+        .accelerator("CmdOrCtrl+N")
+        .build(app_handle)?;
+    let close_table = MenuItemBuilder::with_id(CLOSE_TABLE, "Close Table")
+        .accelerator("CmdOrCtrl+W")
+        .build(app_handle)?;
+    let file = SubmenuBuilder::new(app_handle, "File")
+        .item(&new_table)
+        .item(&close_table);
+    // macOS keeps Quit in the menu named after the app
+    #[cfg(not(target_os = "macos"))]
+    let file = file.separator().quit();
+
+    let mut window = SubmenuBuilder::new(app_handle, "Window").minimize();
+    if !tables.is_empty() {
+        window = window.separator();
+    }
+    for MenuTable { id, title, .. } in tables {
+        let item = MenuItemBuilder::with_id(focus_table_id(*id), title).build(app_handle)?;
+        window = window.item(&item);
+    }
+
+    let menu = MenuBuilder::new(app_handle);
+    // The first submenu is the one named after the app
+    #[cfg(target_os = "macos")]
+    let menu = menu.item(
+        &SubmenuBuilder::new(app_handle, "spa")
+            .about(None)
+            .separator()
+            .hide()
+            .quit()
+            .build()?,
+    );
+    menu.item(&file.build()?).item(&window.build()?).build()
+}
+
+/// Rebuilds the menu to list `tables`.
+pub(crate) fn set_tables(app_handle: &AppHandle, tables: &[MenuTable]) -> tauri::Result<()> {
+    app_handle.set_menu(build(app_handle, tables)?)?;
+    Ok(())
+}
+
+// The table whose window has the focus, if any.
+fn focused_table(app_handle: &AppHandle) -> Option<TableId> {
+    let window = app_handle
+        .webview_windows()
+        .into_values()
+        .find(|w| w.is_focused().unwrap_or(false))?;
+    app_handle.state::<Registry>().id(window.label())
+}
+
+fn focus(window: &WebviewWindow) {
+    let _ = window.unminimize(); // Perhaps we want to log failure
+    let _ = window.set_focus();
+}
+
+fn focus_lobby(app_handle: &AppHandle) {
+    if let Some(lobby) = app_handle.get_webview_window(LOBBY) {
+        focus(&lobby);
+    }
+}
+
+// Done here rather than by the lobby, which may be reloading.
+fn focus_table(app_handle: &AppHandle, id: TableId) {
+    let window = app_handle
+        .state::<Registry>()
+        .label(id)
+        .and_then(|label| app_handle.get_webview_window(&label));
+    match window {
+        None => log::warn!("Table {id} has no window to focus"),
+        Some(window) => focus(&window),
+    }
+}
+
+/// Passes the choice along to the lobby, which owns the tables, unless
+/// it's about a window or the app itself.
+pub(crate) fn handle(app_handle: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    let action = match id {
+        NEW_TABLE => Some(MenuAction::NewTable),
+        CLOSE_TABLE => focused_table(app_handle).map(MenuAction::CloseTable),
+        FOCUS_LOBBY => {
+            focus_lobby(app_handle);
+            None
+        }
+        QUIT => {
+            app_handle.exit(0);
+            None
+        }
+        _ => {
+            if let Some(id) = id
+                .strip_prefix(FOCUS_TABLE_PREFIX)
+                .and_then(|id| id.parse().ok())
+            {
+                focus_table(app_handle, id);
+            }
+            None
+        }
+    };
+    let Some(action) = action else {
+        return;
+    };
+    let lobby = EventTarget::Webview {
+        label: LOBBY.to_string(),
+    };
+    if let Err(e) = app_handle.emit_to(lobby, MENU_EVENT, action) {
+        log::error!("Could not emit {MENU_EVENT}: {e:?}");
+    }
+}
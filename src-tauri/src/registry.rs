@@ -0,0 +1,110 @@
+use {
+    std::{collections::HashMap, sync::Mutex},
+    tauri::{AppHandle, Manager, WebviewWindow, WindowEvent},
+    tauri_command_types::{RegisteredWindow, TableId},
+};
+
+struct Entry {
+    id: TableId,
+    title: String,
+}
+
+/// The table windows `open_window` has created that are still open, by
+/// label.  Unlike the lobby's own list, this outlives a reload of the
+/// lobby's webview.
+#[derive(Default)]
+pub(crate) struct Registry(Mutex<HashMap<String, Entry>>);
+
+impl Registry {
+    pub(crate) fn set_title(&self, app_handle: &AppHandle, label: &str, title: &str) {
+        if let Some(entry) = self.0.lock().unwrap().get_mut(label) {
+            entry.title = title.to_string();
+        }
+        changed(app_handle);
+    }
+
+    /// The label of table `id`'s window, if it's open.
+    pub(crate) fn label(&self, id: TableId) -> Option<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(_, entry)| entry.id == id)
+            .map(|(label, _)| label.clone())
+    }
+
+    /// The table shown in the window labeled `label`, if it's one of
+    /// ours.
+    pub(crate) fn id(&self, label: &str) -> Option<TableId> {
+        self.0.lock().unwrap().get(label).map(|entry| entry.id)
+    }
+
+    /// Every registered window, in table order.
+    pub(crate) fn list(&self, app_handle: &AppHandle) -> Vec<RegisteredWindow> {
+        let mut windows = self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(label, Entry { id, title })| RegisteredWindow {
+                label: label.clone(),
+                id: *id,
+                title: title.clone(),
+                geometry: geometry(app_handle, label),
+            })
+            .collect::<Vec<_>>();
+        windows.sort_by_key(|window| window.id);
+        windows
+    }
+}
+
+#[cfg(desktop)]
+fn geometry(app_handle: &AppHandle, label: &str) -> tauri_command_types::Geometry {
+    app_handle
+        .state::<crate::geometry::Geometries>()
+        .get(label)
+        .unwrap_or_default()
+}
+
+// Mobile windows have nowhere to be
+#[cfg(mobile)]
+fn geometry(_app_handle: &AppHandle, _label: &str) -> tauri_command_types::Geometry {
+    Default::default()
+}
+
+// The tray lists the registered windows.  Called without the lock held,
+// since listing them takes it.
+#[cfg_attr(mobile, expect(unused_variables))]
+fn changed(app_handle: &AppHandle) {
+    #[cfg(desktop)]
+    if let Err(e) = crate::tray::refresh(app_handle) {
+        log::error!("Could not update the tray: {e}");
+    }
+}
+
+/// Adds `window`, which shows table `id`, to the `Registry` until it's
+/// destroyed.
+pub(crate) fn register(window: &WebviewWindow, id: TableId) {
+    let app_handle = window.app_handle().clone();
+    let label = window.label().to_string();
+    let title = window.title().unwrap_or_default();
+    app_handle
+        .state::<Registry>()
+        .0
+        .lock()
+        .unwrap()
+        .insert(label.clone(), Entry { id, title });
+    changed(&app_handle);
+
+    window.on_window_event(move |e| {
+        if let WindowEvent::Destroyed = e {
+            app_handle
+                .state::<Registry>()
+                .0
+                .lock()
+                .unwrap()
+                .remove(&label);
+            changed(&app_handle);
+        }
+    });
+}
@@ -0,0 +1,59 @@
+use {
+    crate::{
+        menu::{FOCUS_LOBBY, NEW_TABLE, QUIT, focus_table_id},
+        registry::Registry,
+    },
+    tauri::{
+        AppHandle, Manager,
+        menu::{Menu, MenuBuilder},
+        tray::TrayIconBuilder,
+    },
+    tauri_command_types::RegisteredWindow,
+};
+
+const TRAY_ID: &str = "main";
+
+// Its items share their ids with the app's menu, whose `menu::handle`
+// also gets the tray's menu events.
+fn build_menu(
+    app_handle: &AppHandle,
+    tables: &[RegisteredWindow],
+) -> tauri::Result<Menu<tauri::Wry>> {
+    let mut menu = MenuBuilder::new(app_handle).text(FOCUS_LOBBY, "Lobby");
+    if !tables.is_empty() {
+        menu = menu.separator();
+    }
+    for RegisteredWindow { id, title, .. } in tables {
+        // Until the lobby titles it
+        let title = match title.as_str() {
+            "" => format!("Table {id}"),
+            title => title.to_string(),
+        };
+        menu = menu.text(focus_table_id(*id), title);
+    }
+    menu.separator()
+        .text(NEW_TABLE, "New Table")
+        .text(QUIT, "Quit")
+        .build()
+}
+
+pub(crate) fn create(app_handle: &AppHandle) -> tauri::Result<()> {
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("spa")
+        .menu(&build_menu(app_handle, &[])?);
+    if let Some(icon) = app_handle.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app_handle)?;
+    Ok(())
+}
+
+/// Rebuilds the tray's menu to list the windows in the `Registry`, which
+/// keeps it right even while the lobby's webview is reloading.
+pub(crate) fn refresh(app_handle: &AppHandle) -> tauri::Result<()> {
+    if let Some(tray) = app_handle.tray_by_id(TRAY_ID) {
+        let tables = app_handle.state::<Registry>().list(app_handle);
+        tray.set_menu(Some(build_menu(app_handle, &tables)?))?;
+    }
+    Ok(())
+}
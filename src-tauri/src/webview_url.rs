@@ -0,0 +1,136 @@
+use {
+    tauri::{Url, WebviewUrl},
+    tauri_command_types::CommandError,
+};
+
+// Where the app serves its own pages: tauri://localhost on macOS,
+// Linux and iOS, and http://tauri.localhost (https if so configured) on
+// Windows and Android.
+fn is_app_origin(url: &Url) -> bool {
+    match (url.scheme(), url.host_str()) {
+        ("tauri", Some("localhost")) => true,
+        ("http" | "https", Some("tauri.localhost")) => url.port().is_none(),
+        _ => false,
+    }
+}
+
+fn same_origin(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// What a window opened on `url` should load.  `url` has to be one of
+/// our own pages, served either by the app itself or, in development,
+/// by the server at `dev_url`.
+pub(crate) fn parse(url: &str, dev_url: Option<&Url>) -> Result<WebviewUrl, CommandError> {
+    let parsed = Url::parse(url).map_err(|e| CommandError::InvalidUrl {
+        url: url.to_string(),
+        reason: e.to_string(),
+    })?;
+    if dev_url.is_some_and(|dev_url| same_origin(&parsed, dev_url)) {
+        return Ok(WebviewUrl::External(parsed));
+    }
+    if !is_app_origin(&parsed) {
+        return Err(CommandError::ForeignOrigin(url.to_string()));
+    }
+    let mut path = parsed.path().trim_start_matches('/').to_string();
+    if let Some(query) = parsed.query() {
+        path.push('?');
+        path.push_str(query);
+    }
+    if let Some(fragment) = parsed.fragment() {
+        path.push('#');
+        path.push_str(fragment);
+    }
+    Ok(WebviewUrl::App(path.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEV_URL: &str = "http://localhost:1420";
+
+    fn dev_url() -> Url {
+        DEV_URL.parse().unwrap()
+    }
+
+    fn app_path(url: &str) -> Option<String> {
+        match parse(url, Some(&dev_url())) {
+            Ok(WebviewUrl::App(path)) => Some(path.to_string_lossy().into_owned()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn macos() {
+        assert_eq!(
+            app_path("tauri://localhost/table/3").as_deref(),
+            Some("table/3")
+        );
+    }
+
+    #[test]
+    fn windows() {
+        assert_eq!(
+            app_path("http://tauri.localhost/table/3").as_deref(),
+            Some("table/3")
+        );
+        assert_eq!(
+            app_path("https://tauri.localhost/table/3").as_deref(),
+            Some("table/3")
+        );
+    }
+
+    #[test]
+    fn keeps_query_and_fragment() {
+        assert_eq!(
+            app_path("http://tauri.localhost/table/3?seat=2#chat").as_deref(),
+            Some("table/3?seat=2#chat")
+        );
+        assert_eq!(app_path("tauri://localhost/?x=1").as_deref(), Some("?x=1"));
+    }
+
+    #[test]
+    fn dev() {
+        let url = format!("{DEV_URL}/table/3?seat=2#chat");
+        match parse(&url, Some(&dev_url())) {
+            Ok(WebviewUrl::External(external)) => assert_eq!(external.as_str(), url),
+            other => panic!("expected External, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn dev_url_only_in_development() {
+        let url = format!("{DEV_URL}/table/3");
+        assert_eq!(
+            parse(&url, None).unwrap_err(),
+            CommandError::ForeignOrigin(url)
+        );
+    }
+
+    #[test]
+    fn rejects_other_origins() {
+        for url in [
+            "https://example.com/table/3",
+            "http://localhost:1421/table/3",
+            "tauri://example.com/table/3",
+            "http://tauri.localhost:8080/table/3",
+            "file:///table/3",
+        ] {
+            assert_eq!(
+                parse(url, Some(&dev_url())).unwrap_err(),
+                CommandError::ForeignOrigin(url.to_string()),
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(matches!(
+            parse("table/3", None),
+            Err(CommandError::InvalidUrl { .. })
+        ));
+    }
+}
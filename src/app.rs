@@ -1,12 +1,9 @@
 use {
-    crate::{Lobby, Table, TableId},
+    crate::{AppBar, Lobby, Table, TableId, Tables, mode},
     yew::prelude::*,
     yew_router::prelude::*,
 };
 
-#[cfg(feature = "spa")]
-use crate::Tables;
-
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Routable)]
 pub enum Route {
     #[at("/table/:id")]
@@ -17,53 +14,56 @@ pub enum Route {
 }
 
 pub(crate) struct App {
-    #[cfg(feature = "spa")]
     tables: Tables,
 }
 
 impl Component for App {
+    /// The lobby has reordered the tables.
     type Message = ();
     type Properties = ();
 
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
-            #[cfg(feature = "spa")]
             tables: Default::default(),
         }
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
-        #[cfg(not(feature = "spa"))]
-        fn switch(route: Route) -> Html {
-            match route {
-                Route::Index => html! { <Lobby /> },
-                Route::Table { id } => html! { <Table {id} /> },
-            }
-        }
-
-        #[cfg(feature = "spa")]
-        let switch = {
-            let tables = self.tables.clone();
-            let tables_too = tables.clone();
+    // Rendering again gives the tables their new neighbors
+    fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
+        true
+    }
 
-            move |route: Route| {
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let tables = self.tables.clone();
+        let on_order_change = ctx.link().callback(|()| ());
+        let switch = move |route: Route| {
+            if mode::is_spa() {
                 let id = match route {
                     Route::Index => None,
                     Route::Table { id } => Some(id),
                 };
-                let tables = tables.clone();
                 html! {
                     <>
-                        <Lobby {tables} show={id.is_none()} />
+                        <AppBar label={route.to_path()} />
+                        <Lobby
+                            tables={tables.clone()}
+                            show={id.is_none()}
+                            on_order_change={on_order_change.clone()}
+                        />
                         {
-                            tables_too.triple_html(|(left, this, right)| {
+                            tables.triple_html(|(left, this, right)| {
                                 html! {
-                                    <Table key={this} id={this} show={id == Some(this)} {left} {right} />
+                                    <Table key={this.to_string()} id={this} show={id == Some(this)} {left} {right} />
                                 }
                             })
                         }
                     </>
                 }
+            } else {
+                match route {
+                    Route::Index => html! { <Lobby tables={tables.clone()} /> },
+                    Route::Table { id } => html! { <Table {id} /> },
+                }
             }
         };
 
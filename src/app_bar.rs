@@ -0,0 +1,48 @@
+use {
+    crate::window::{TitleWatch, spa_title, watch_titles},
+    yew::prelude::*,
+};
+
+#[derive(Clone, Properties, PartialEq)]
+pub(crate) struct Properties {
+    /// The label (route's path) of whatever's being shown.
+    pub(crate) label: String,
+}
+
+/// The title of the route being shown in spa mode, where there's no
+/// title bar per table.
+pub(crate) struct AppBar {
+    _watch: TitleWatch,
+}
+
+impl AppBar {
+    fn title(ctx: &Context<Self>) -> String {
+        spa_title(&ctx.props().label).unwrap_or_default()
+    }
+}
+
+impl Component for AppBar {
+    type Message = ();
+    type Properties = Properties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            _watch: watch_titles(ctx.link().callback(|()| ())),
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, _msg: Self::Message) -> bool {
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <header class="app-bar">{ Self::title(ctx) }</header>
+        }
+    }
+
+    // Keeps the browser's tab, if there is one, in agreement.
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        gloo_utils::document().set_title(&Self::title(ctx));
+    }
+}
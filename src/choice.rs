@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+/// An enum whose variants the user picks from, e.g. with a `Select`.
+/// `as_str` is how a variant is stored and `label` is how it's shown.
+///
+/// Implemented with `impl_choice!`, which implements `FromStr` too.
+pub(crate) trait Choice: Copy + Eq + FromStr<Err = String> + 'static {
+    /// Every variant, in the order they're offered.
+    const ALL: &'static [Self];
+
+    fn as_str(self) -> &'static str;
+
+    fn label(self) -> &'static str;
+}
+
+/// Implements `Choice` and `FromStr` for an enum from its variants,
+/// each with its `as_str` and `label`.
+macro_rules! impl_choice {
+    ($name:ident { $($variant:ident => ($as_str:literal, $label:literal)),+ $(,)? }) => {
+        impl $crate::choice::Choice for $name {
+            const ALL: &'static [Self] = &[$(Self::$variant),+];
+
+            fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $as_str),+
+                }
+            }
+
+            fn label(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $label),+
+                }
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                use $crate::choice::Choice;
+
+                Self::ALL
+                    .iter()
+                    .copied()
+                    .find(|choice| choice.as_str() == s)
+                    .ok_or_else(|| format!("unknown {}: {s}", stringify!($name)))
+            }
+        }
+    };
+}
+
+pub(crate) use impl_choice;
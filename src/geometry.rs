@@ -0,0 +1,65 @@
+use {
+    crate::{
+        mode::{self, Mode, Platform},
+        storage,
+    },
+    gloo_events::EventListener,
+    tauri_command_types::{Geometry, Position, Size},
+};
+
+// Under Tauri, src-tauri keeps track of window geometry itself; this
+// is for web multi-window mode, where it lives in localStorage.
+
+const KEY_PREFIX: &str = "spa-geometry:";
+
+fn key(label: &str) -> String {
+    format!("{KEY_PREFIX}{label}")
+}
+
+/// Where the window labeled `label` was when it last closed.
+pub(crate) fn saved(label: &str) -> Option<Geometry> {
+    storage::load(&key(label))
+}
+
+pub(crate) fn inner_size(window: &web_sys::Window) -> Option<Size> {
+    let dimension = |v: Result<wasm_bindgen::JsValue, _>| v.ok()?.as_f64().map(|d| d as u32);
+    dimension(window.inner_width())
+        .zip(dimension(window.inner_height()))
+        .map(|(width, height)| Size { height, width })
+}
+
+fn current() -> Geometry {
+    let window = gloo_utils::window();
+    let size = inner_size(&window);
+    let position = window
+        .screen_x()
+        .ok()
+        .zip(window.screen_y().ok())
+        .map(|(left, top)| Position { top, left });
+    Geometry { size, position }
+}
+
+fn save(label: &str) {
+    storage::save(&key(label), &current());
+}
+
+/// Saves the current window's geometry whenever it's resized and when
+/// it's closed.  Browsers have no "moved" event, so the position is
+/// only picked up at those times.
+pub(crate) struct Tracker {
+    _resize: EventListener,
+    _unload: EventListener,
+}
+
+pub(crate) fn track(label: String) -> Option<Tracker> {
+    if mode::platform() != Platform::Web || mode::mode() != Mode::MultiWindow {
+        return None;
+    }
+    let window = gloo_utils::window();
+    let _resize = {
+        let label = label.clone();
+        EventListener::new(&window, "resize", move |_| save(&label))
+    };
+    let _unload = EventListener::new(&window, "beforeunload", move |_| save(&label));
+    Some(Tracker { _resize, _unload })
+}
@@ -0,0 +1,73 @@
+use {
+    crate::{
+        TableInfo,
+        choice::impl_choice,
+        storage,
+        table_info::Sort,
+        table_meta::{Game, Status},
+    },
+    serde::{Deserialize, Serialize},
+};
+
+/// Whether a table's window is minimized (or, in a browser, in a
+/// background tab).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Visibility {
+    #[default]
+    All,
+    Visible,
+    Hidden,
+}
+
+impl_choice!(Visibility {
+    All => ("all", "Visible or Hidden"),
+    Visible => ("visible", "Visible"),
+    Hidden => ("hidden", "Hidden"),
+});
+
+/// Which tables the lobby lists.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Filter {
+    /// Matched against each table's name and id, ignoring case.
+    pub(crate) search: String,
+    pub(crate) status: Option<Status>,
+    pub(crate) game: Option<Game>,
+    pub(crate) visibility: Visibility,
+}
+
+impl Filter {
+    pub(crate) fn matches(&self, info: &TableInfo) -> bool {
+        let search = self.search.trim().to_lowercase();
+        let found = search.is_empty()
+            || info.meta.name.to_lowercase().contains(&search)
+            || info.id.to_string() == search;
+        let visible = match self.visibility {
+            Visibility::All => true,
+            Visibility::Visible => !info.minimized,
+            Visibility::Hidden => info.minimized,
+        };
+        found
+            && visible
+            && self.status.is_none_or(|status| status == info.meta.status)
+            && self.game.is_none_or(|game| game == info.meta.game)
+    }
+}
+
+/// How the lobby lists its tables, remembered in localStorage.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Listing {
+    pub(crate) sort: Sort,
+    pub(crate) filter: Filter,
+}
+
+const LISTING_KEY: &str = "spa-listing";
+
+impl Listing {
+    pub(crate) fn load() -> Self {
+        storage::load(LISTING_KEY).unwrap_or_default()
+    }
+
+    pub(crate) fn save(&self) {
+        storage::save(LISTING_KEY, self);
+    }
+}
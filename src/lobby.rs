@@ -1,63 +1,146 @@
 use {
-    crate::{Route, TableId, TableInfo, TableWindow, Tables},
-    serde::{Deserialize, Serialize},
-    std::sync::atomic::{AtomicU8, Ordering},
-    tauri_command_types::PopUpFeatures,
-    yew::prelude::*,
+    crate::{
+        Route, Table, TableId, TableInfo, TableWindow, Tables, WindowBackend,
+        choice::Choice,
+        geometry,
+        listing::{Listing, Visibility},
+        mode::{self, ModeSetting, Platform},
+        placement::{self, Placement},
+        select::Select,
+        session::{self, RestoreSetting, Session},
+        table, table_ids,
+        table_info::{Column, Sort},
+        table_meta::{Game, Status, TableMeta},
+        util::timezone_from_browser_or_mountain,
+        window::{self, Error, Listener, Reachable},
+    },
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize, de::DeserializeOwned},
+    tauri_command_types::{
+        Geometry, LOBBY, LifecycleNotification, MenuAction, MenuTable, PopUpFeatures,
+        RegisteredWindow, SetMenuTables, WindowEvent, WindowLifecycleEvent,
+    },
+    web_sys::HtmlInputElement,
+    yew::{html::Scope, prelude::*},
     yew_router::prelude::*,
 };
 
-#[cfg(all(feature = "tauri", not(feature = "spa")))]
-use tauri_command_types::CloseNotification;
-
 #[derive(Clone, Properties, PartialEq)]
 pub(crate) struct Properties {
-    #[cfg(feature = "spa")]
     pub(crate) tables: Tables,
 
-    #[cfg(feature = "spa")]
+    #[prop_or(true)]
     pub(crate) show: bool,
+
+    /// Called when the tables' order changes, which in spa mode changes
+    /// where the tables' left and right buttons go.
+    #[prop_or_default]
+    pub(crate) on_order_change: Callback<()>,
 }
 
 impl Properties {
     fn class(&self) -> Option<&'static str> {
-        #[cfg(not(feature = "spa"))]
-        {
-            None
-        }
-
-        #[cfg(feature = "spa")]
-        {
-            (!self.show).then_some("hide")
-        }
+        (!self.show).then_some("hide")
     }
 }
 
+/// What a table can send to the lobby from its own window.
 #[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Remote {
+    /// The table has (re)loaded and wants its name.
+    Ready(TableId),
+    CloseWindow(TableId),
+    UpdateMeta(TableId, TableMeta),
+}
+
+#[derive(Clone, Eq, PartialEq)]
 pub enum Msg {
     CreateWindow,
     CloseWindow(TableId),
+    FocusWindow(TableId),
+    SetTitle(String, String),
+    DeepLink(String),
+    TableWindowEvent(TableId, WindowEvent),
+    /// From a table that has just (re)loaded.
+    TableReady(TableId),
+    /// From the table itself.
+    UpdateMeta(TableId, TableMeta),
+    StartRename(TableId),
+    EditName(String),
+    FinishRename,
+    CancelRename,
+    SortBy(Column),
+    Search(String),
+    FilterStatus(Option<Status>),
+    FilterGame(Option<Game>),
+    FilterVisibility(Visibility),
+    Lifecycle(WindowLifecycleEvent),
+    Reattach(Vec<RegisteredWindow>),
+    ChangeModeSetting(ModeSetting),
+    ChangePlacement(Placement),
+    ChangeRestoreSetting(RestoreSetting),
+    Restore,
+    DismissRestore,
+    LocationChanged,
+    EditAnnouncement(String),
+    Announce,
+    WindowOpened(TableId),
+    OpenFailed(TableId, String),
+    PopupBlocked(TableId),
+    RetryOpen(TableId),
+    ShowInline(TableId),
+    DismissBlocked(TableId),
+    DismissError,
 }
 
 pub(crate) struct Lobby {
-    #[cfg(not(feature = "spa"))]
-    tables: Tables,
-    #[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
-    _child_listener: gloo_events::EventListener,
+    announcement: String,
+    error: Option<String>,
+    /// Tables whose windows a popup blocker stopped, until they're
+    /// opened, shown inline or dismissed.  They're still part of the
+    /// saved session meanwhile.
+    blocked: Vec<TableId>,
+    placement: Placement,
+    listing: Listing,
+    /// The table being renamed and the name typed so far.
+    renaming: Option<(TableId, String)>,
+    rename_input: NodeRef,
+    /// Set when renaming starts, so that the input is focused once
+    /// it's been rendered.
+    focus_rename: bool,
+    /// What the desktop app's Window menu was last told to list.
+    menu_tables: Vec<MenuTable>,
+    restore_setting: RestoreSetting,
+    /// The last session's tables, while we're asking whether to reopen
+    /// them.
+    previous: Option<Session>,
+    /// What was last saved as the session.
+    saved: Option<Session>,
+    /// In spa mode, what to show once restored tables have opened,
+    /// rather than each table in turn.
+    shown_after_restore: Option<Route>,
+    /// The tables' ids as of the last sort.
+    order: Vec<TableId>,
+    // In spa mode, going from one table to another doesn't re-render us
+    _location_listener: Option<LocationHandle>,
+    _child_listener: Option<Listener>,
 }
 
-static TABLE_NUMBER: AtomicU8 = AtomicU8::new(0);
-
-#[cfg(not(feature = "spa"))]
-fn features(id: TableId) -> PopUpFeatures {
-    use tauri_command_types::Size;
-
+/// `placed` is where the lobby's `Placement` wants the table, if it
+/// has an opinion.
+fn features(id: TableId, placed: Option<Geometry>) -> PopUpFeatures {
     let path = Route::Table { id }.to_path();
 
-    #[cfg(all(feature = "tauri", not(feature = "spa")))]
-    let close_notification = Some(CloseNotification {
-        receiver_label: "main".to_string(),
-        id: id.into(),
+    let restore_geometry = placed.is_none();
+    let geometry = match (placed, mode::platform()) {
+        (Some(placed), _) => placed,
+        (None, Platform::Web) => geometry::saved(&path).unwrap_or_default(),
+        // src-tauri applies saved geometry itself
+        (None, Platform::Tauri) => Geometry::default(),
+    };
+
+    let lifecycle = (mode::platform() == Platform::Tauri).then(|| LifecycleNotification {
+        receiver_label: LOBBY.to_string(),
     });
 
     let location = gloo_utils::window().location();
@@ -67,115 +150,533 @@ fn features(id: TableId) -> PopUpFeatures {
     PopUpFeatures {
         url,
         target: path,
-        size: Some(Size {
-            height: 200,
-            width: 300,
-        }),
-        position: None,
-        #[cfg(all(feature = "tauri", not(feature = "spa")))]
-        close_notification,
+        id,
+        size: geometry.size.or(Some(placement::TABLE_SIZE)),
+        position: geometry.position,
+        restore_geometry,
+        lifecycle,
     }
 }
 
-#[cfg(feature = "spa")]
-fn features(id: TableId) -> PopUpFeatures {
-    PopUpFeatures {
-        path: Route::Table { id }.to_path(),
+impl Lobby {
+    fn create_window(&mut self, ctx: &Context<Self>) -> bool {
+        self.shown_after_restore = None;
+        let id = table_ids::allocate(self.tables(ctx));
+        self.open_window(id, ctx)
     }
-}
 
-fn table_id(id: TableId) -> Html {
-    html! {
-        <> { " Table " } { id } </>
+    fn open_window(&mut self, id: TableId, ctx: &Context<Self>) -> bool {
+        let link = ctx.link().clone();
+        let tables = self.tables(ctx).clone();
+        let placed = self.placement.place(&tables.geometries());
+        let features = features(id, placed);
+        let geometry = Geometry {
+            size: features.size,
+            position: features.position,
+        };
+        let opening = TableWindow::open(features, true);
+        let was_blocked = self.unblock(id);
+        ctx.link().send_future(async move {
+            match opening.await {
+                Ok(window) => {
+                    // Only a window that actually opened gets a TableInfo,
+                    // and only one, even if the same link arrived again
+                    // while it was opening.
+                    if !tables.contains(id) {
+                        tables.push(TableInfo::new(&link, window, id, geometry));
+                    }
+                    Msg::WindowOpened(id)
+                }
+                Err(Error::NoWindow) => Msg::PopupBlocked(id),
+                Err(e) => {
+                    log::error!("new window failed: {e:?}");
+                    Msg::OpenFailed(id, e.to_string())
+                }
+            }
+        });
+        was_blocked
     }
-}
 
-#[cfg(feature = "spa")]
-fn linked_table_id(id: TableId) -> Html {
-    html! {
-        <Link<Route> to={Route::Table { id }}>
-            { table_id(id) }
-        </Link<Route>>
+    /// Shows table `id` within the lobby's page instead of in a window.
+    fn show_inline(&mut self, id: TableId, ctx: &Context<Self>) -> bool {
+        self.unblock(id);
+        let window = TableWindow::inline(Route::Table { id });
+        let info = TableInfo::new(ctx.link(), window, id, Geometry::default());
+        self.tables(ctx).push(info);
+        true
     }
-}
 
-impl Lobby {
-    #[cfg(all(feature = "tauri", not(feature = "spa")))]
-    fn new() -> Self {
-        Self {
-            #[cfg(not(feature = "spa"))]
-            tables: Default::default(),
+    /// Whether table `id` was blocked, which it no longer is.
+    fn unblock(&mut self, id: TableId) -> bool {
+        let count = self.blocked.len();
+        self.blocked.retain(|blocked| *blocked != id);
+        self.blocked.len() != count
+    }
+
+    fn blocked_view(&self, ctx: &Context<Self>) -> Html {
+        let blocked = |id: TableId| {
+            let retry = ctx.link().callback(move |_| Msg::RetryOpen(id));
+            let inline = ctx.link().callback(move |_| Msg::ShowInline(id));
+            let dismiss = ctx.link().callback(move |_| Msg::DismissBlocked(id));
+            html! {
+                <div class="error" key={id.to_string()}>
+                    { format!("Your browser blocked the window for Table {id}.") }
+                    <button onclick={retry}>{ "Try Again" }</button>
+                    <button onclick={inline}>{ "Show Here" }</button>
+                    <button onclick={dismiss}>{ "Dismiss" }</button>
+                </div>
+            }
+        };
+        self.blocked.iter().copied().map(blocked).collect()
+    }
+
+    fn navigate(&self, route: Route, ctx: &Context<Self>) {
+        match ctx.link().navigator() {
+            None => log::error!("no navigator"),
+            Some(navigator) => navigator.replace(&route),
         }
     }
 
-    #[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
-    fn new(_child_listener: gloo_events::EventListener) -> Self {
-        Self {
-            #[cfg(not(feature = "spa"))]
-            tables: Default::default(),
-            _child_listener,
+    fn window_opened(&mut self, id: TableId, ctx: &Context<Self>) -> bool {
+        if mode::is_spa() {
+            let route = self.shown_after_restore.unwrap_or(Route::Table { id });
+            self.navigate(route, ctx);
         }
+        true
     }
 
-    fn create_window(&mut self, ctx: &Context<Self>) -> bool {
-        let link = ctx.link();
-        let id = TABLE_NUMBER.fetch_add(1, Ordering::Relaxed);
-        match TableWindow::new(features(id), false) {
-            Ok(window) => {
-                self.tables_mut(ctx).push(TableInfo::new(link, window, id));
+    fn error_view(&self, ctx: &Context<Self>) -> Option<Html> {
+        let error = self.error.as_ref()?;
+        let onclick = ctx.link().callback(|_| Msg::DismissError);
+        Some(html! {
+            <div class="error">
+                { error }
+                <button {onclick}>{ "Dismiss" }</button>
+            </div>
+        })
+    }
 
-                #[cfg(feature = "spa")]
-                {
-                    match ctx.link().navigator() {
-                        None => log::error!("no navigator"),
-                        Some(navigator) => navigator.replace(&Route::Table { id }),
-                    }
+    fn close_window(&mut self, id: TableId, ctx: &Context<Self>) -> bool {
+        // In spa mode, closing the table that's being shown means
+        // showing something else.
+        let showing = mode::is_spa() && ctx.link().route::<Route>() == Some(Route::Table { id });
+        let neighbor = self.tables(ctx).neighbor(id);
+        let removed = self.tables(ctx).remove_by_id(id);
+        if removed && showing {
+            self.navigate(neighbor.unwrap_or(Route::Index), ctx);
+        }
+        removed
+    }
+
+    /// Shows whatever a link like spa://table/12 refers to, opening the
+    /// table if it isn't already.
+    fn deep_link(&mut self, path: String, ctx: &Context<Self>) -> bool {
+        self.shown_after_restore = None;
+        match Route::recognize(&path) {
+            None => {
+                log::warn!("Unrecognized link: {path}");
+                false
+            }
+            Some(Route::Index) => {
+                if mode::is_spa() {
+                    self.navigate(Route::Index, ctx);
                 }
+                false
+            }
+            Some(Route::Table { id }) if self.tables(ctx).contains(id) => {
+                self.tables(ctx).focus_by_id(id);
+                false
+            }
+            Some(Route::Table { id }) => {
+                table_ids::reserve(id);
+                self.open_window(id, ctx)
             }
-            Err(e) => log::error!("new window failed: {e:?}"),
+        }
+    }
+
+    fn lifecycle(&mut self, event: WindowLifecycleEvent, ctx: &Context<Self>) -> bool {
+        use WindowLifecycleEvent::*;
+
+        match event {
+            // The TableInfo is pushed once open_window's future resolves
+            Created(_) => false,
+            Focused(id) => self.tables(ctx).apply_event(id, WindowEvent::Focused),
+            CloseRequested(id) => {
+                self.tables(ctx).send_to(id, &table::Remote::Closing);
+                false
+            }
+            Closed(id) => self.close_window(id, ctx),
+        }
+    }
+
+    /// Picks up the table windows src-tauri still has open, e.g. because
+    /// the lobby's page was reloaded.
+    fn reattach(&mut self, windows: Vec<RegisteredWindow>, ctx: &Context<Self>) -> bool {
+        let tables = self.tables(ctx);
+        let mut changed = false;
+        for RegisteredWindow {
+            label,
+            id,
+            title,
+            geometry,
+        } in windows
+        {
+            if tables.contains(id) {
+                continue;
+            }
+            table_ids::reserve(id);
+            let window = TableWindow::reattach(label);
+            let mut info = TableInfo::new(ctx.link(), window, id, geometry);
+            // The title is the name we gave it, perhaps a rename, until
+            // it tells us otherwise.
+            if !title.is_empty() {
+                info.meta.name = title;
+            }
+            tables.push(info);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Reopens the tables that were open at last exit, the same way
+    /// links to them would.
+    fn restore(&mut self, ctx: &Context<Self>) -> bool {
+        let Some(Session { paths, current }) = self.previous.take() else {
+            return false;
+        };
+        for path in paths {
+            self.deep_link(path, ctx);
+        }
+        if mode::is_spa() {
+            let current = current.and_then(|path| Route::recognize(&path));
+            self.shown_after_restore = Some(current.unwrap_or(Route::Index));
         }
         true
     }
 
-    fn close_window(&mut self, id: TableId, ctx: &Context<Self>) -> bool {
-        self.tables_mut(ctx).remove_by_id(id)
+    fn restore_view(&self, ctx: &Context<Self>) -> Option<Html> {
+        let count = self.previous.as_ref()?.paths.len();
+        let tables = if count == 1 { "table" } else { "tables" };
+        let restore = ctx.link().callback(|_| Msg::Restore);
+        let dismiss = ctx.link().callback(|_| Msg::DismissRestore);
+        Some(html! {
+            <div>
+                { format!("Reopen the {count} {tables} from last time?") }
+                <button onclick={restore}>{ "Reopen" }</button>
+                <button onclick={dismiss}>{ "No Thanks" }</button>
+            </div>
+        })
+    }
+
+    /// Saves the tables as the session, if they've changed since they
+    /// were last saved.
+    fn save_session(&mut self, ctx: &Context<Self>) {
+        // Until the user decides, the last session is still worth keeping
+        if self.previous.is_some() {
+            return;
+        }
+        let current = mode::is_spa()
+            .then(|| ctx.link().route::<Route>())
+            .flatten()
+            .map(|route| route.to_path());
+        let mut paths = self.tables(ctx).paths();
+        // A blocked table is still wanted, just not yet open
+        for id in &self.blocked {
+            let path = Route::Table { id: *id }.to_path();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        let session = Session { paths, current };
+        if self.saved.as_ref() == Some(&session) {
+            return;
+        }
+        session.save();
+        self.saved = Some(session);
     }
 
-    #[cfg(not(feature = "spa"))]
-    fn tables(&self, _ctx: &Context<Self>) -> &Tables {
-        &self.tables
+    /// Puts the tables in the listing's order, letting our parent know
+    /// if that's a change.
+    fn sort_tables(&mut self, ctx: &Context<Self>) {
+        let tables = self.tables(ctx);
+        tables.sort(self.listing.sort);
+        let order = tables.ids();
+        if order != self.order {
+            self.order = order;
+            ctx.props().on_order_change.emit(());
+        }
+    }
+
+    /// Has the desktop app's Window menu list the tables, if they've
+    /// changed since it was last told.
+    fn sync_menu(&mut self, ctx: &Context<Self>) {
+        if mode::platform() != Platform::Tauri || mode::is_spa() {
+            return;
+        }
+        let tables = self.tables(ctx).menu_tables();
+        if tables == self.menu_tables {
+            return;
+        }
+        self.menu_tables = tables.clone();
+        yew::platform::spawn_local(async move {
+            if let Err(e) = tauri_command_types::invoke(&SetMenuTables { tables }).await {
+                log::error!("Could not update the menu: {e}");
+            }
+        });
     }
 
-    #[cfg(feature = "spa")]
     fn tables<'a>(&self, ctx: &'a Context<Self>) -> &'a Tables {
         &ctx.props().tables
     }
 
-    #[cfg(not(feature = "spa"))]
-    fn tables_mut(&mut self, _ctx: &Context<Self>) -> &mut Tables {
-        &mut self.tables
+    fn start_rename(&mut self, id: TableId, ctx: &Context<Self>) -> bool {
+        let Some(name) = self.tables(ctx).meta(id).map(|meta| meta.name) else {
+            return false;
+        };
+        self.renaming = Some((id, name));
+        self.focus_rename = true;
+        true
     }
 
-    #[cfg(feature = "spa")]
-    fn tables_mut<'a>(&self, ctx: &'a Context<Self>) -> &'a Tables {
-        &ctx.props().tables
+    fn finish_rename(&mut self, ctx: &Context<Self>) -> bool {
+        let Some((id, name)) = self.renaming.take() else {
+            return false;
+        };
+        // A blank name would leave nothing to click on
+        let name = name.trim();
+        if !name.is_empty() {
+            self.tables(ctx).rename(id, name.to_string());
+        }
+        true
+    }
+
+    fn name_view(&self, info: &TableInfo, ctx: &Context<Self>) -> Html {
+        match &self.renaming {
+            Some((id, name)) if *id == info.id => {
+                let oninput = ctx.link().callback(|e: InputEvent| {
+                    Msg::EditName(e.target_unchecked_into::<HtmlInputElement>().value())
+                });
+                let onkeydown =
+                    ctx.link()
+                        .batch_callback(|e: KeyboardEvent| match e.key().as_str() {
+                            "Enter" => Some(Msg::FinishRename),
+                            "Escape" => Some(Msg::CancelRename),
+                            _ => None,
+                        });
+                let onblur = ctx.link().callback(|_| Msg::FinishRename);
+                html! {
+                    <input
+                        ref={self.rename_input.clone()}
+                        value={name.clone()}
+                        {oninput}
+                        {onkeydown}
+                        {onblur}
+                    />
+                }
+            }
+            _ => {
+                let id = info.id;
+                let rename = ctx.link().callback(move |_| Msg::StartRename(id));
+                html! {
+                    <>
+                        <a class="table-name" onclick={info.focus_callback.clone()}>
+                            { &info.meta.name }
+                        </a>
+                        <span class="rename" onclick={rename} title="Rename">{ " ✏️" }</span>
+                    </>
+                }
+            }
+        }
+    }
+
+    fn change_listing(&mut self, f: impl FnOnce(&mut Listing)) -> bool {
+        f(&mut self.listing);
+        self.listing.save();
+        true
+    }
+
+    fn filter_view(&self, ctx: &Context<Self>) -> Html {
+        let Listing { filter, .. } = &self.listing;
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            Msg::Search(e.target_unchecked_into::<HtmlInputElement>().value())
+        });
+        let on_status = ctx.link().callback(Msg::FilterStatus);
+        let on_game = ctx.link().callback(Msg::FilterGame);
+        let on_visibility = ctx
+            .link()
+            .batch_callback(|v: Option<_>| v.map(Msg::FilterVisibility));
+        html! {
+            <div class="table-filter">
+                <input value={filter.search.clone()} {oninput} placeholder="Search" />
+                <Select<Status> value={filter.status} onchange={on_status} any="Any Status" />
+                <Select<Game> value={filter.game} onchange={on_game} any="Any Game" />
+                <Select<Visibility> value={filter.visibility} onchange={on_visibility} />
+            </div>
+        }
+    }
+
+    fn heading_view(&self, column: Column, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.link().callback(move |_| Msg::SortBy(column));
+        let arrow = match self.listing.sort {
+            Sort {
+                column: sorted,
+                descending,
+            } if sorted == column => {
+                if descending {
+                    " ▼"
+                } else {
+                    " ▲"
+                }
+            }
+            _ => "",
+        };
+        html! {
+            <th {onclick}>{ column.label() }{ arrow }</th>
+        }
     }
 
     fn tables_view(&self, ctx: &Context<Self>) -> Html {
-        #[cfg(feature = "spa")]
-        use linked_table_id as table_id;
+        let timezone = timezone_from_browser_or_mountain();
+        let table_view = |info: &TableInfo| {
+            let class = classes!(
+                info.focused.then_some("focused"),
+                info.minimized.then_some("minimized"),
+            );
+            let time = |t: DateTime<Utc>| t.with_timezone(&timezone).format("%H:%M:%S").to_string();
+            html! {
+                <tr {class}>
+                    <td onclick={info.close_callback.clone()}>
+                        { "🗑️" }
+                    </td>
+                    <td>{ info.id.to_string() }</td>
+                    <td>{ self.name_view(info, ctx) }</td>
+                    <td>{ info.meta.game.label() }</td>
+                    <td>{ time(info.created) }</td>
+                    <td>{ time(info.active) }</td>
+                    <td>{ info.meta.status.label() }</td>
+                    <td>{ info.meta.participants }</td>
+                </tr>
+            }
+        };
 
         html! {
-            <ol class="tables-three-columns"> {
-                self.tables(ctx).html(|TableInfo { id, close_callback, .. }| html! {
-                    <li>
-                        <span onclick={close_callback.clone()}>
-                            { "🗑️" }
-                        </span>
-                        { table_id(*id) }
-                    </li>
-                })
-            } </ol>
+            <table class="tables">
+                <thead>
+                    <tr>
+                        <th></th>
+                        { for Column::ALL.into_iter().map(|column| self.heading_view(column, ctx)) }
+                    </tr>
+                </thead>
+                <tbody> {
+                    self.tables(ctx).html(|info| self.listing.filter.matches(info), table_view)
+                } </tbody>
+            </table>
+        }
+    }
+
+    fn announce(&mut self, ctx: &Context<Self>) -> bool {
+        let announcement = std::mem::take(&mut self.announcement);
+        if announcement.is_empty() {
+            return false;
+        }
+        self.tables(ctx)
+            .broadcast(&table::Remote::Announce(announcement));
+        true
+    }
+
+    fn announcement_view(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            Msg::EditAnnouncement(e.target_unchecked_into::<HtmlInputElement>().value())
+        });
+        let onclick = ctx.link().callback(|_| Msg::Announce);
+        html! {
+            <div>
+                <input value={self.announcement.clone()} {oninput} placeholder="Announcement" />
+                <button {onclick}>{"Announce"}</button>
+            </div>
+        }
+    }
+
+    fn placement_view(&self, ctx: &Context<Self>) -> Option<Html> {
+        // Tables in a single page have nowhere to be placed
+        if mode::is_spa() {
+            return None;
+        }
+        let onchange = ctx
+            .link()
+            .batch_callback(|p: Option<_>| p.map(Msg::ChangePlacement));
+        Some(html! {
+            <label>
+                { "New tables: " }
+                <Select<Placement> value={self.placement} {onchange} />
+            </label>
+        })
+    }
+
+    fn restore_setting_view(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx
+            .link()
+            .batch_callback(|s: Option<_>| s.map(Msg::ChangeRestoreSetting));
+        html! {
+            <label>
+                { "Reopen tables from last time: " }
+                <Select<RestoreSetting> value={self.restore_setting} {onchange} />
+            </label>
+        }
+    }
+
+    fn mode_setting_view(&self, ctx: &Context<Self>) -> Html {
+        let current = mode::setting();
+        let disabled = mode::is_forced();
+        let onchange = ctx
+            .link()
+            .batch_callback(|s: Option<_>| s.map(Msg::ChangeModeSetting));
+        html! {
+            <label>
+                { "Windows: " }
+                <Select<ModeSetting> value={current} {disabled} {onchange} />
+            </label>
+        }
+    }
+}
+
+/// Sends the lobby `f` of the payload of every `event` src-tauri emits
+/// to it.  Tables aren't allowed to emit events, and even if they were,
+/// they couldn't aim them at us.
+fn forward_events<T: DeserializeOwned + 'static>(
+    event: &'static str,
+    link: &Scope<Lobby>,
+    f: impl Fn(T) -> Option<Msg> + 'static,
+) {
+    use {
+        futures::StreamExt,
+        tauri_sys::event::{EventTarget, listen_to},
+    };
+
+    let link = link.clone();
+    yew::platform::spawn_local(async move {
+        let lobby = EventTarget::Webview(LOBBY.to_string());
+        match listen_to::<T>(event, lobby).await {
+            Err(e) => log::error!("Can't listen_to({event}, ...): {e:?}"),
+            Ok(mut events) => {
+                while let Some(event) = events.next().await {
+                    if let Some(msg) = f(event.payload) {
+                        link.send_message(msg);
+                    }
+                }
+            }
+        }
+    });
+}
+
+impl Reachable for Lobby {
+    type Remote = Remote;
+
+    fn receive(remote: Remote) -> Msg {
+        match remote {
+            Remote::Ready(id) => Msg::TableReady(id),
+            Remote::CloseWindow(id) => Msg::CloseWindow(id),
+            Remote::UpdateMeta(id, meta) => Msg::UpdateMeta(id, meta),
         }
     }
 }
@@ -184,53 +685,197 @@ impl Component for Lobby {
     type Message = Msg;
     type Properties = Properties;
 
-    #[cfg_attr(feature = "spa", expect(unused))]
     fn create(ctx: &Context<Self>) -> Self {
-        #[cfg(feature = "spa")]
-        {
-            Self {}
+        use tauri_command_types::{
+            CLOSE_WINDOW_EVENT, Close, DEEP_LINK_EVENT, LIFECYCLE_EVENT, ListWindows, MENU_EVENT,
+            SET_TITLE_EVENT, SetTitle, TakeDeepLink,
+        };
+
+        match (mode::platform(), mode::is_spa()) {
+            (Platform::Tauri, false) => {
+                forward_events(LIFECYCLE_EVENT, ctx.link(), |event| {
+                    Some(Msg::Lifecycle(event))
+                });
+                forward_events(MENU_EVENT, ctx.link(), |action| {
+                    Some(match action {
+                        MenuAction::NewTable => Msg::CreateWindow,
+                        MenuAction::CloseTable(id) => Msg::CloseWindow(id),
+                    })
+                });
+                let link = ctx.link().clone();
+                yew::platform::spawn_local(async move {
+                    match tauri_command_types::invoke(&ListWindows).await {
+                        Err(e) => log::error!("Could not list windows: {e}"),
+                        Ok(windows) => link.send_message(Msg::Reattach(windows)),
+                    }
+                });
+            }
+            // Tauri Mobile asks us to do what it can't.
+            (Platform::Tauri, true) => {
+                forward_events(SET_TITLE_EVENT, ctx.link(), |s: SetTitle| {
+                    Some(Msg::SetTitle(s.label, s.title))
+                });
+                forward_events(
+                    CLOSE_WINDOW_EVENT,
+                    ctx.link(),
+                    |c: Close| match Route::recognize(&c.label) {
+                        Some(Route::Table { id }) => Some(Msg::CloseWindow(id)),
+                        _ => {
+                            log::warn!("Can't close {}", c.label);
+                            None
+                        }
+                    },
+                );
+            }
+            (Platform::Web, _) => {}
         }
 
-        #[cfg(not(feature = "spa"))]
-        {
-            let link = ctx.link().clone();
-
-            #[cfg(feature = "tauri")]
-            {
-                use {
-                    futures::StreamExt,
-                    tauri_command_types::CLOSED_EVENT,
-                    tauri_sys::event::{EventTarget, listen_to},
-                };
-
+        match mode::platform() {
+            Platform::Tauri => {
+                forward_events(DEEP_LINK_EVENT, ctx.link(), |path| {
+                    Some(Msg::DeepLink(path))
+                });
+                let link = ctx.link().clone();
                 yew::platform::spawn_local(async move {
-                    match listen_to::<u64>(CLOSED_EVENT, EventTarget::Any).await {
-                        Err(e) => log::error!("Can't listen_to(CLOSED_EVENT, ...): {e:?}"),
-                        Ok(mut events) => {
-                            while let Some(event) = events.next().await {
-                                link.send_message(Msg::CloseWindow(event.payload as u8));
-                            }
-                        }
+                    match tauri_command_types::invoke(&TakeDeepLink).await {
+                        Err(e) => log::error!("Could not take deep link: {e}"),
+                        Ok(None) => {}
+                        Ok(Some(path)) => link.send_message(Msg::DeepLink(path)),
                     }
                 });
-                Self::new()
             }
-
-            #[cfg(not(feature = "tauri"))]
-            {
-                let window = crate::Window::<Self>::current();
-                Self::new(window.listener(link))
+            // In spa mode the page may have been loaded with a table's
+            // url, but that table doesn't exist until we open it.
+            Platform::Web => {
+                if mode::is_spa()
+                    && let Some(route @ Route::Table { .. }) = ctx.link().route::<Route>()
+                {
+                    ctx.link().send_message(Msg::DeepLink(route.to_path()));
+                }
             }
         }
+
+        let restore_setting = session::setting();
+        let previous = match restore_setting {
+            RestoreSetting::Never => None,
+            RestoreSetting::Ask | RestoreSetting::Always => session::previous(),
+        };
+        if previous.is_some() && restore_setting == RestoreSetting::Always {
+            ctx.link().send_message(Msg::Restore);
+        }
+
+        let window = crate::Window::<Self>::current_at(Route::Index);
+        window.set_title("Lobby".to_string());
+        Self {
+            announcement: String::new(),
+            error: None,
+            blocked: Vec::new(),
+            placement: placement::setting(),
+            listing: Listing::load(),
+            renaming: None,
+            rename_input: NodeRef::default(),
+            focus_rename: false,
+            menu_tables: Vec::new(),
+            restore_setting,
+            previous,
+            saved: None,
+            shown_after_restore: None,
+            order: Vec::new(),
+            _location_listener: mode::is_spa()
+                .then(|| {
+                    ctx.link()
+                        .add_location_listener(ctx.link().callback(|_| Msg::LocationChanged))
+                })
+                .flatten(),
+            _child_listener: window.listen(ctx.link().clone()),
+        }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         use Msg::*;
 
-        match msg {
+        let changed = match msg {
             CreateWindow => self.create_window(ctx),
             CloseWindow(id) => self.close_window(id, ctx),
+            FocusWindow(id) => {
+                self.tables(ctx).focus_by_id(id);
+                false
+            }
+            SetTitle(label, title) => {
+                window::set_spa_title(&label, title);
+                false
+            }
+            DeepLink(path) => self.deep_link(path, ctx),
+            TableWindowEvent(id, event) => self.tables(ctx).apply_event(id, event),
+            TableReady(id) => {
+                self.tables(ctx).retitle(id);
+                false
+            }
+            UpdateMeta(id, meta) => self.tables(ctx).set_meta(id, meta),
+            StartRename(id) => self.start_rename(id, ctx),
+            EditName(name) => {
+                if let Some((_, editing)) = &mut self.renaming {
+                    *editing = name;
+                }
+                false
+            }
+            FinishRename => self.finish_rename(ctx),
+            CancelRename => self.renaming.take().is_some(),
+            SortBy(column) => self.change_listing(|listing| listing.sort = listing.sort.by(column)),
+            Search(search) => self.change_listing(|listing| listing.filter.search = search),
+            FilterStatus(status) => self.change_listing(|listing| listing.filter.status = status),
+            FilterGame(game) => self.change_listing(|listing| listing.filter.game = game),
+            FilterVisibility(visibility) => {
+                self.change_listing(|listing| listing.filter.visibility = visibility)
+            }
+            Lifecycle(event) => self.lifecycle(event, ctx),
+            Reattach(windows) => self.reattach(windows, ctx),
+            ChangeModeSetting(setting) => {
+                mode::change_setting(setting);
+                false
+            }
+            ChangeRestoreSetting(setting) => {
+                session::change_setting(setting);
+                self.restore_setting = setting;
+                false
+            }
+            Restore => self.restore(ctx),
+            DismissRestore => self.previous.take().is_some(),
+            LocationChanged => {
+                self.save_session(ctx);
+                false
+            }
+            ChangePlacement(placement) => {
+                placement::change_setting(placement);
+                self.placement = placement;
+                false
+            }
+            EditAnnouncement(announcement) => {
+                self.announcement = announcement;
+                false
+            }
+            Announce => self.announce(ctx),
+            WindowOpened(id) => self.window_opened(id, ctx),
+            OpenFailed(id, error) => {
+                self.error = Some(format!("Could not open Table {id}: {error}"));
+                true
+            }
+            DismissError => self.error.take().is_some(),
+            PopupBlocked(id) => {
+                if !self.blocked.contains(&id) {
+                    self.blocked.push(id);
+                }
+                true
+            }
+            RetryOpen(id) => self.open_window(id, ctx),
+            ShowInline(id) => self.show_inline(id, ctx),
+            DismissBlocked(id) => self.unblock(id),
+        };
+        // Whatever changed what we show may have changed the order too
+        if changed {
+            self.sort_tables(ctx);
         }
+        changed
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -239,8 +884,35 @@ impl Component for Lobby {
         html! {
             <div {class}>
                 <button {onclick}>{"Create Window"}</button>
+                { self.error_view(ctx) }
+                { self.blocked_view(ctx) }
+                { self.restore_view(ctx) }
+                { self.filter_view(ctx) }
                 { self.tables_view(ctx) }
+                { self.announcement_view(ctx) }
+                { self.placement_view(ctx) }
+                { self.restore_setting_view(ctx) }
+                { self.mode_setting_view(ctx) }
+                {
+                    self.tables(ctx).inline_html(|id| html! {
+                        <Table key={id.to_string()} {id} inline={true} />
+                    })
+                }
             </div>
         }
     }
+
+    // Tables come and go without our being told directly, so we check
+    // after each render.
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        // autofocus only works when the page loads
+        if std::mem::take(&mut self.focus_rename)
+            && let Some(input) = self.rename_input.cast::<HtmlInputElement>()
+        {
+            let _ = input.focus(); // Perhaps we want to log failure
+            input.select();
+        }
+        self.sync_menu(ctx);
+        self.save_session(ctx);
+    }
 }
@@ -1,24 +1,35 @@
 mod app;
+mod app_bar;
+mod choice;
+mod geometry;
+mod listing;
 mod lobby;
+mod mode;
+mod placement;
+mod select;
+mod session;
+mod storage;
 mod table;
+mod table_ids;
 mod table_info;
+mod table_meta;
 mod util;
 mod window;
 
 pub(crate) use {
     app::Route,
+    app_bar::AppBar,
     lobby::Lobby,
     table::Table,
     table_info::{TableInfo, TableWindow, Tables},
-    window::Window,
+    window::{Window, WindowBackend},
 };
 
 use app::App;
 use js_sys::{JSON, JsString};
+use tauri_command_types::TableId;
 use wasm_bindgen::{JsCast, prelude::*};
 
-type TableId = u8;
-
 pub(crate) fn human(value: JsValue) -> String {
     if let Some(string) = value.as_string() {
         string
@@ -0,0 +1,128 @@
+use {
+    crate::{choice::impl_choice, storage},
+    serde::{Deserialize, Serialize},
+    std::sync::OnceLock,
+    wasm_bindgen::JsValue,
+};
+
+/// What we're running inside of.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Platform {
+    Tauri,
+    Web,
+}
+
+/// How tables are shown: each in its own window, or as routes within a
+/// single page.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Mode {
+    MultiWindow,
+    SinglePage,
+}
+
+/// The user's choice of `Mode`, remembered in localStorage.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum ModeSetting {
+    #[default]
+    Auto,
+    MultiWindow,
+    SinglePage,
+}
+
+impl_choice!(ModeSetting {
+    Auto => ("auto", "Automatic"),
+    MultiWindow => ("multi-window", "Multiple Windows"),
+    SinglePage => ("single-page", "Single Page"),
+});
+
+const MODE_SETTING_KEY: &str = "spa-mode-setting";
+
+// Screens narrower than this get the single-page treatment.  We look
+// at the screen rather than the viewport, because table windows are
+// deliberately small and must agree with the lobby about the mode.
+const NARROW_SCREEN_WIDTH: i32 = 768;
+
+static DETECTED: OnceLock<(Platform, Mode)> = OnceLock::new();
+
+pub(crate) fn platform() -> Platform {
+    detected().0
+}
+
+pub(crate) fn mode() -> Mode {
+    detected().1
+}
+
+pub(crate) fn is_spa() -> bool {
+    mode() == Mode::SinglePage
+}
+
+/// True when the mode is fixed by the platform, regardless of setting.
+pub(crate) fn is_forced() -> bool {
+    platform() == Platform::Tauri && is_mobile()
+}
+
+fn detected() -> (Platform, Mode) {
+    *DETECTED.get_or_init(|| {
+        let platform = if is_tauri() {
+            Platform::Tauri
+        } else {
+            Platform::Web
+        };
+        let mode = if platform == Platform::Tauri && is_mobile() {
+            // Tauri Mobile has no way to open a second window
+            Mode::SinglePage
+        } else {
+            match setting() {
+                ModeSetting::Auto if is_mobile() || is_narrow() => Mode::SinglePage,
+                ModeSetting::Auto => Mode::MultiWindow,
+                ModeSetting::MultiWindow => Mode::MultiWindow,
+                ModeSetting::SinglePage => Mode::SinglePage,
+            }
+        };
+        log::info!("platform: {platform:?}, mode: {mode:?}");
+        (platform, mode)
+    })
+}
+
+fn is_tauri() -> bool {
+    js_sys::Reflect::has(
+        &gloo_utils::window(),
+        &JsValue::from_str("__TAURI_INTERNALS__"),
+    )
+    .unwrap_or(false)
+}
+
+fn is_mobile() -> bool {
+    gloo_utils::window()
+        .navigator()
+        .user_agent()
+        .map(|agent| {
+            ["Android", "iPhone", "iPad", "iPod"]
+                .iter()
+                .any(|name| agent.contains(name))
+        })
+        .unwrap_or(false)
+}
+
+fn is_narrow() -> bool {
+    gloo_utils::window()
+        .screen()
+        .and_then(|screen| screen.width())
+        .map(|width| width < NARROW_SCREEN_WIDTH)
+        .unwrap_or(false)
+}
+
+pub(crate) fn setting() -> ModeSetting {
+    storage::setting(MODE_SETTING_KEY)
+}
+
+/// Remembers `setting` and reloads, since the mode can't change while
+/// windows or routes for the old mode exist.
+pub(crate) fn change_setting(setting: ModeSetting) {
+    if !storage::change_setting(MODE_SETTING_KEY, setting) {
+        return;
+    }
+    if let Err(e) = gloo_utils::window().location().reload() {
+        log::error!("could not reload: {}", crate::human(e));
+    }
+}
@@ -0,0 +1,263 @@
+use {
+    crate::{choice::impl_choice, storage},
+    serde::{Deserialize, Serialize},
+    tauri_command_types::{Geometry, Position, Size},
+};
+
+/// How the lobby decides where a new table window goes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Placement {
+    /// Wherever that table was last time, otherwise wherever the OS or
+    /// browser likes.
+    #[default]
+    Remembered,
+    /// A little down and to the right of the newest window.
+    Cascade,
+    /// Table-sized cells across the screen, filled in order.
+    Grid,
+    /// The first grid cell that no open window overlaps.
+    NextFreeSlot,
+}
+
+impl_choice!(Placement {
+    Remembered => ("remembered", "Where It Was"),
+    Cascade => ("cascade", "Cascade"),
+    Grid => ("grid", "Grid"),
+    NextFreeSlot => ("next-free-slot", "Next Free Slot"),
+});
+
+impl Placement {
+    /// Where a new table should go, given the geometry of the tables
+    /// that are already open, oldest first.  `None` means we have no
+    /// opinion.
+    pub(crate) fn place(self, open: &[Geometry]) -> Option<Geometry> {
+        let screen = screen_size()?;
+        let size = Size {
+            height: TABLE_SIZE.height.min(screen.height),
+            width: TABLE_SIZE.width.min(screen.width),
+        };
+        let grid = Grid::new(screen, size);
+        let position = match self {
+            Self::Remembered => return None,
+            Self::Cascade => cascade(screen, size, open),
+            Self::Grid => grid.cell(open.len()),
+            Self::NextFreeSlot => grid
+                .free_cell(size, open)
+                .unwrap_or_else(|| cascade(screen, size, open)),
+        };
+        Some(Geometry {
+            size: Some(size),
+            position: Some(position),
+        })
+    }
+}
+
+const PLACEMENT_KEY: &str = "spa-placement";
+
+/// How big a table window is unless it remembers otherwise.
+pub(crate) const TABLE_SIZE: Size = Size {
+    height: 200,
+    width: 300,
+};
+
+const CASCADE_STEP: i32 = 30;
+
+pub(crate) fn setting() -> Placement {
+    storage::setting(PLACEMENT_KEY)
+}
+
+pub(crate) fn change_setting(placement: Placement) {
+    storage::change_setting(PLACEMENT_KEY, placement);
+}
+
+// The part of the screen that isn't taken up by task bars and the
+// like.  We treat its top left corner as the origin, which is only
+// approximately right when the lobby is on a secondary monitor.
+fn screen_size() -> Option<Size> {
+    let screen = gloo_utils::window().screen().ok()?;
+    let width = screen.avail_width().ok()?;
+    let height = screen.avail_height().ok()?;
+    Some(Size {
+        height: height.max(0) as u32,
+        width: width.max(0) as u32,
+    })
+}
+
+fn cascade(screen: Size, size: Size, open: &[Geometry]) -> Position {
+    let origin = Position { top: 0, left: 0 };
+    let Some(newest) = open.iter().rev().find_map(|g| g.position) else {
+        return origin;
+    };
+    let next = Position {
+        top: newest.top + CASCADE_STEP,
+        left: newest.left + CASCADE_STEP,
+    };
+    if fits(next, size, screen) {
+        next
+    } else {
+        origin
+    }
+}
+
+fn fits(Position { top, left }: Position, size: Size, screen: Size) -> bool {
+    top >= 0
+        && left >= 0
+        && top + size.height as i32 <= screen.height as i32
+        && left + size.width as i32 <= screen.width as i32
+}
+
+fn overlaps(position: Position, size: Size, other: &Geometry) -> bool {
+    let Some(o) = other.position else {
+        return false;
+    };
+    let o_size = other.size.unwrap_or(TABLE_SIZE);
+    position.left < o.left + o_size.width as i32
+        && o.left < position.left + size.width as i32
+        && position.top < o.top + o_size.height as i32
+        && o.top < position.top + size.height as i32
+}
+
+struct Grid {
+    columns: usize,
+    rows: usize,
+    size: Size,
+}
+
+impl Grid {
+    fn new(screen: Size, size: Size) -> Self {
+        let count = |available: u32, each: u32| (available / each.max(1)).max(1) as usize;
+        Self {
+            columns: count(screen.width, size.width),
+            rows: count(screen.height, size.height),
+            size,
+        }
+    }
+
+    // Wraps back to the top left once the screen is full.
+    fn cell(&self, n: usize) -> Position {
+        let n = n % (self.columns * self.rows);
+        Position {
+            top: (n / self.columns) as i32 * self.size.height as i32,
+            left: (n % self.columns) as i32 * self.size.width as i32,
+        }
+    }
+
+    fn free_cell(&self, size: Size, open: &[Geometry]) -> Option<Position> {
+        (0..self.columns * self.rows)
+            .map(|n| self.cell(n))
+            .find(|&cell| !open.iter().any(|g| overlaps(cell, size, g)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Three tables across and three down.
+    const SCREEN: Size = Size {
+        height: 600,
+        width: 900,
+    };
+
+    fn at(top: i32, left: i32) -> Position {
+        Position { top, left }
+    }
+
+    fn window(top: i32, left: i32) -> Geometry {
+        Geometry {
+            size: Some(TABLE_SIZE),
+            position: Some(at(top, left)),
+        }
+    }
+
+    #[test]
+    fn cascade_starts_at_origin() {
+        assert_eq!(cascade(SCREEN, TABLE_SIZE, &[]), at(0, 0));
+    }
+
+    #[test]
+    fn cascade_steps_from_newest() {
+        let open = [window(100, 100), window(0, 0)];
+        assert_eq!(cascade(SCREEN, TABLE_SIZE, &open), at(30, 30));
+    }
+
+    #[test]
+    fn cascade_skips_windows_without_position() {
+        let open = [window(10, 10), Geometry::default()];
+        assert_eq!(cascade(SCREEN, TABLE_SIZE, &open), at(40, 40));
+    }
+
+    #[test]
+    fn cascade_wraps_to_origin() {
+        // 380 + 30 + 200 is below the bottom of the screen
+        assert_eq!(cascade(SCREEN, TABLE_SIZE, &[window(380, 100)]), at(0, 0));
+        // 580 + 30 + 300 is past its right edge
+        assert_eq!(cascade(SCREEN, TABLE_SIZE, &[window(100, 580)]), at(0, 0));
+    }
+
+    #[test]
+    fn fits_up_to_the_edges() {
+        assert!(fits(at(400, 600), TABLE_SIZE, SCREEN));
+        assert!(!fits(at(401, 600), TABLE_SIZE, SCREEN));
+        assert!(!fits(at(400, 601), TABLE_SIZE, SCREEN));
+        assert!(!fits(at(-1, 0), TABLE_SIZE, SCREEN));
+    }
+
+    #[test]
+    fn overlaps_only_when_windows_share_area() {
+        assert!(overlaps(at(0, 0), TABLE_SIZE, &window(199, 299)));
+        assert!(!overlaps(at(0, 0), TABLE_SIZE, &window(200, 0)));
+        assert!(!overlaps(at(0, 0), TABLE_SIZE, &window(0, 300)));
+        assert!(!overlaps(at(0, 0), TABLE_SIZE, &Geometry::default()));
+    }
+
+    #[test]
+    fn grid_fills_rows_then_wraps() {
+        let grid = Grid::new(SCREEN, TABLE_SIZE);
+        assert_eq!(grid.cell(0), at(0, 0));
+        assert_eq!(grid.cell(2), at(0, 600));
+        assert_eq!(grid.cell(3), at(200, 0));
+        assert_eq!(grid.cell(8), at(400, 600));
+        assert_eq!(grid.cell(9), at(0, 0));
+        assert_eq!(grid.cell(10), at(0, 300));
+    }
+
+    #[test]
+    fn grid_on_a_small_screen_has_one_cell() {
+        let screen = Size {
+            height: 100,
+            width: 100,
+        };
+        let grid = Grid::new(screen, TABLE_SIZE);
+        assert_eq!(grid.cell(0), at(0, 0));
+        assert_eq!(grid.cell(5), at(0, 0));
+    }
+
+    #[test]
+    fn free_cell_skips_overlapped_cells() {
+        let grid = Grid::new(SCREEN, TABLE_SIZE);
+        let small = Size {
+            height: 100,
+            width: 100,
+        };
+        let open = [
+            window(0, 0),
+            // Straddles the second and fifth cells
+            Geometry {
+                size: Some(small),
+                position: Some(at(150, 350)),
+            },
+        ];
+        assert_eq!(grid.free_cell(TABLE_SIZE, &open), Some(at(0, 600)));
+    }
+
+    #[test]
+    fn free_cell_finds_nothing_on_a_full_screen() {
+        let grid = Grid::new(SCREEN, TABLE_SIZE);
+        let covering = Geometry {
+            size: Some(SCREEN),
+            position: Some(at(0, 0)),
+        };
+        assert_eq!(grid.free_cell(TABLE_SIZE, &[covering]), None);
+    }
+}
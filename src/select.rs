@@ -0,0 +1,52 @@
+use {crate::choice::Choice, web_sys::HtmlSelectElement, yew::prelude::*};
+
+#[derive(Clone, Properties, PartialEq)]
+pub(crate) struct Properties<T: Choice> {
+    /// Only `None` when there's an `any` option.
+    pub(crate) value: Option<T>,
+    pub(crate) onchange: Callback<Option<T>>,
+    /// The label of an extra first option, which stands for no choice
+    /// at all.
+    #[prop_or_default]
+    pub(crate) any: Option<&'static str>,
+    #[prop_or_default]
+    pub(crate) disabled: bool,
+}
+
+/// A `<select>` offering every variant of `T`.
+#[function_component]
+pub(crate) fn Select<T: Choice>(props: &Properties<T>) -> Html {
+    let Properties {
+        value,
+        onchange,
+        any,
+        disabled,
+    } = props.clone();
+
+    let onchange = Callback::from(move |e: Event| {
+        let selected = e.target_unchecked_into::<HtmlSelectElement>().value();
+        // "" is the `any` option
+        if selected.is_empty() {
+            onchange.emit(None);
+            return;
+        }
+        match selected.parse() {
+            Err(e) => log::error!("{e}"),
+            Ok(choice) => onchange.emit(Some(choice)),
+        }
+    });
+    html! {
+        <select {disabled} {onchange}>
+            if let Some(any) = any {
+                <option value="" selected={value.is_none()}>{ any }</option>
+            }
+            {
+                for T::ALL.iter().map(|&choice| html! {
+                    <option value={choice.as_str()} selected={value == Some(choice)}>
+                        { choice.label() }
+                    </option>
+                })
+            }
+        </select>
+    }
+}
@@ -0,0 +1,74 @@
+use {
+    crate::{choice::impl_choice, human, storage, util::session_storage},
+    serde::{Deserialize, Serialize},
+};
+
+/// The tables that were open, saved to localStorage whenever they
+/// change so that the next launch can reopen them.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Session {
+    /// The route of each table, in the lobby's order.  A table's id is
+    /// part of its route.
+    pub(crate) paths: Vec<String>,
+    /// In spa mode, the route that was being shown.
+    pub(crate) current: Option<String>,
+}
+
+const SESSION_KEY: &str = "spa-session";
+
+// Set in sessionStorage once the lobby has looked at the saved
+// session, which is how we tell a reload from a launch.
+const STARTED_KEY: &str = "spa-session-started";
+
+impl Session {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    fn load() -> Option<Self> {
+        storage::load(SESSION_KEY)
+    }
+
+    pub(crate) fn save(&self) {
+        storage::save(SESSION_KEY, self);
+    }
+}
+
+/// What was open when the app last exited, unless there was nothing or
+/// this is merely the lobby being reloaded.
+pub(crate) fn previous() -> Option<Session> {
+    if let Some(storage) = session_storage() {
+        if storage.get_item(STARTED_KEY).ok().flatten().is_some() {
+            return None;
+        }
+        if let Err(e) = storage.set_item(STARTED_KEY, "true") {
+            log::error!("could not mark session started: {}", human(e));
+        }
+    }
+    Session::load().filter(|session| !session.is_empty())
+}
+
+/// Whether the lobby reopens the previous session's tables by itself.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum RestoreSetting {
+    #[default]
+    Ask,
+    Always,
+    Never,
+}
+
+impl_choice!(RestoreSetting {
+    Ask => ("ask", "Ask"),
+    Always => ("always", "Always"),
+    Never => ("never", "Never"),
+});
+
+const RESTORE_SETTING_KEY: &str = "spa-restore";
+
+pub(crate) fn setting() -> RestoreSetting {
+    storage::setting(RESTORE_SETTING_KEY)
+}
+
+pub(crate) fn change_setting(setting: RestoreSetting) {
+    storage::change_setting(RESTORE_SETTING_KEY, setting);
+}
@@ -0,0 +1,57 @@
+use {
+    crate::{choice::Choice, human, util::local_storage},
+    js_sys::JSON,
+    serde::{Serialize, de::DeserializeOwned},
+};
+
+// Everything the app remembers across launches lives in localStorage,
+// either as JSON or, for settings, as a bare string.
+
+/// What was saved under `key`, if there's anything readable.
+pub(crate) fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let json = local_storage()?.get_item(key).ok().flatten()?;
+    let value = JSON::parse(&json)
+        .inspect_err(|e| log::warn!("bad {key}: {}", human(e.clone())))
+        .ok()?;
+    serde_wasm_bindgen::from_value(value)
+        .inspect_err(|e| log::warn!("bad {key}: {e}"))
+        .ok()
+}
+
+pub(crate) fn save<T: Serialize>(key: &str, value: &T) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let json = serde_wasm_bindgen::to_value(value)
+        .map_err(|e| e.to_string())
+        .and_then(|value| JSON::stringify(&value).map_err(human));
+    match json {
+        Err(e) => log::error!("could not serialize {key}: {e}"),
+        Ok(json) => {
+            if let Err(e) = storage.set_item(key, &String::from(json)) {
+                log::error!("could not save {key}: {}", human(e));
+            }
+        }
+    }
+}
+
+/// The setting stored under `key`, or its default if there's none.
+pub(crate) fn setting<T: Choice + Default>(key: &str) -> T {
+    local_storage()
+        .and_then(|storage| storage.get_item(key).ok().flatten())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Remembers `value` as the setting stored under `key`.  False if it
+/// couldn't be.
+pub(crate) fn change_setting<T: Choice>(key: &str, value: T) -> bool {
+    let Some(storage) = local_storage() else {
+        log::error!("no localStorage, can't remember {key}");
+        return false;
+    };
+    storage
+        .set_item(key, value.as_str())
+        .inspect_err(|e| log::error!("could not store {key}: {}", human(e.clone())))
+        .is_ok()
+}
@@ -1,120 +1,271 @@
-use {crate::TableId, yew::prelude::*};
-
-#[cfg(feature = "spa")]
-use crate::Route;
+use {
+    crate::{
+        Route, TableId, WindowBackend, geometry,
+        mode::{self, Mode, Platform},
+        select::Select,
+        table_meta::{Game, Status, TableMeta},
+        window::{Listener, Reachable, TitleWatch, spa_title, watch_titles},
+    },
+    serde::{Deserialize, Serialize},
+    yew::prelude::*,
+    yew_router::Routable,
+};
 
 #[derive(Clone, Properties, PartialEq)]
 pub(crate) struct Properties {
     pub(crate) id: TableId,
-    #[cfg(feature = "spa")]
+    #[prop_or(true)]
     pub(crate) show: bool,
-    #[cfg(feature = "spa")]
+    #[prop_or_default]
     pub(crate) left: Option<Route>,
-    #[cfg(feature = "spa")]
+    #[prop_or_default]
     pub(crate) right: Option<Route>,
+    /// Shown within the lobby's page because its window couldn't be
+    /// opened.
+    #[prop_or_default]
+    pub(crate) inline: bool,
 }
 
 impl Properties {
     fn class(&self) -> Option<&'static str> {
-        #[cfg(not(feature = "spa"))]
-        {
-            None
-        }
-
-        #[cfg(feature = "spa")]
-        {
-            (!self.show).then_some("hide")
-        }
+        (!self.show).then_some("hide")
     }
 }
 
+/// What the lobby can send to a table, which may be in another window.
+#[derive(Clone, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Remote {
+    Announce(String),
+    Closing,
+    Rename(String),
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub enum Msg {
+    Announce(String),
+    Closing,
+    ChangeGame(Game),
+    ChangeStatus(Status),
+    SitDown,
+    StandUp,
+    Rename(String),
+    /// In spa mode, some table's title changed, perhaps a neighbor's.
+    TitlesChanged,
+}
+
 pub(crate) struct Table {
     state: String, // This is just a proof of concept
-    #[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
-    _unload_listener: gloo_events::EventListener,
+    meta: TableMeta,
+    announcements: Vec<String>,
+    closing: bool,
+    _unload_listener: Option<gloo_events::EventListener>,
+    _lobby_listener: Option<Listener>,
+    _geometry_tracker: Option<geometry::Tracker>,
+    _title_watch: Option<TitleWatch>,
 }
 
-#[cfg_attr(not(feature = "spa"), expect(unused_variables))]
 fn nav_buttons(ctx: &Context<Table>) -> Option<Html> {
-    #[cfg(not(feature = "spa"))]
-    {
-        None
+    use yew_router::prelude::*;
+
+    fn button(label: String, route: Option<Route>, n: &Navigator) -> Html {
+        let disabled = route.is_none();
+        let onclick: Option<Callback<MouseEvent>> = route.map(|r| {
+            let n = n.clone();
+            { move |_| n.replace(&r) }.into()
+        });
+        html! {
+            <button {disabled} {onclick}> { label } </button>
+        }
     }
 
-    #[cfg(feature = "spa")]
-    {
-        use {crate::Route, yew_router::prelude::*};
-        fn button(label: &'static str, route: Option<Route>, n: &Navigator) -> Html {
-            let disabled = route.is_none();
-            let onclick: Option<Callback<MouseEvent>> = route.map(|r| {
-                let n = n.clone();
-                { move |_| n.replace(&r) }.into()
-            });
-            html! {
-                <button {disabled} {onclick}> { label } </button>
-            }
+    if !mode::is_spa() {
+        return None;
+    }
+
+    match ctx.link().navigator() {
+        None => {
+            log::error!("No navigator");
+            None
+        }
+        Some(n) => {
+            let Properties { left, right, .. } = *ctx.props();
+            // Next to each arrow goes the name of the table it leads to
+            let title = |route: Option<Route>| route.and_then(|r| spa_title(&r.to_path()));
+            let left_label = title(left).map_or("⬅️".to_string(), |t| format!("⬅️ {t}"));
+            let right_label = title(right).map_or("➡️️".to_string(), |t| format!("{t} ➡️️"));
+            let left = button(left_label, left, &n);
+            let right = button(right_label, right, &n);
+            let goto_lobby: Callback<MouseEvent> = { move |_| n.replace(&Route::Index) }.into();
+            Some(html! {
+                <div id="nav-overlay">
+                    { left }
+                    <button onclick={goto_lobby}> {"Lobby️"} </button>
+                    { right }
+                </div>
+            })
         }
+    }
+}
 
-        match ctx.link().navigator() {
-            None => {
-                log::error!("No navigator");
-                None
-            }
-            Some(n) => {
-                let left = button("⬅️", ctx.props().left, &n);
-                let right = button("➡️️", ctx.props().right, &n);
-                let goto_lobby: Callback<MouseEvent> = { move |_| n.replace(&Route::Index) }.into();
-                Some(html! {
-                    <div id="nav-overlay">
-                        { left }
-                        <button onclick={goto_lobby}> {"Lobby️"} </button>
-                        { right }
-                    </div>
-                })
+/// Sends `msg` to the lobby that table `ctx.props().id` belongs to.
+fn tell_lobby(ctx: &Context<Table>, msg: &crate::lobby::Remote) {
+    use crate::{Lobby, Window};
+
+    let Properties { id, inline, .. } = *ctx.props();
+    // An inline table shares the lobby's page
+    let lobby = if inline {
+        Ok(Window::<Lobby>::current_at(Route::Index))
+    } else {
+        Window::<Lobby>::opener()
+    };
+    match lobby {
+        Err(e) => log::error!("No lobby to tell about table {id}: {e}"),
+        Ok(lobby) => {
+            if let Err(e) = lobby.send(msg) {
+                log::error!("Could not tell the lobby about table {id}: {e}");
             }
         }
     }
 }
 
+impl Table {
+    /// Changes what the lobby shows about us and tells it so.
+    fn update_meta(&mut self, ctx: &Context<Self>, f: impl FnOnce(&mut TableMeta)) {
+        f(&mut self.meta);
+        let id = ctx.props().id;
+        tell_lobby(
+            ctx,
+            &crate::lobby::Remote::UpdateMeta(id, self.meta.clone()),
+        );
+    }
+
+    fn meta_view(&self, ctx: &Context<Self>) -> Html {
+        let on_game = ctx
+            .link()
+            .batch_callback(|g: Option<_>| g.map(Msg::ChangeGame));
+        let on_status = ctx
+            .link()
+            .batch_callback(|s: Option<_>| s.map(Msg::ChangeStatus));
+        let sit_down = ctx.link().callback(|_| Msg::SitDown);
+        let stand_up = ctx.link().callback(|_| Msg::StandUp);
+        let TableMeta {
+            game,
+            status,
+            participants,
+            ..
+        } = self.meta;
+        html! {
+            <div class="table-meta">
+                <Select<Game> value={game} onchange={on_game} />
+                <Select<Status> value={status} onchange={on_status} />
+                { format!(" Players: {participants} ") }
+                <button onclick={sit_down}>{ "Sit Down" }</button>
+                <button onclick={stand_up} disabled={participants == 0}>{ "Stand Up" }</button>
+            </div>
+        }
+    }
+}
+
+impl Reachable for Table {
+    type Remote = Remote;
+
+    fn receive(remote: Remote) -> Msg {
+        match remote {
+            Remote::Announce(announcement) => Msg::Announce(announcement),
+            Remote::Closing => Msg::Closing,
+            Remote::Rename(name) => Msg::Rename(name),
+        }
+    }
+}
+
 impl Component for Table {
-    type Message = ();
+    type Message = Msg;
     type Properties = Properties;
 
     fn create(ctx: &Context<Self>) -> Self {
         use crate::util::{timezone_from_browser_or_mountain, utc_now};
         let now = utc_now().with_timezone(&timezone_from_browser_or_mountain());
 
-        let id = ctx.props().id;
-        crate::Window::<Self>::current().set_title(format!("Table {id}"));
-
-        #[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
-        let _unload_listener = {
-            use crate::Window;
-
-            let window = gloo_utils::window();
-            gloo_events::EventListener::new(&window, "beforeunload", move |_| {
-                if let Ok(parent) = Window::<crate::Lobby>::opener()
-                    && let Err(e) = parent.send(&crate::lobby::Msg::CloseWindow(id))
-                {
-                    log::error!("Could not send CloseWindow({id}): {e}");
-                }
-            })
+        let Properties { id, inline, .. } = *ctx.props();
+        let route = Route::Table { id };
+        let window = if inline {
+            crate::Window::<Self>::inline(route)
+        } else {
+            crate::Window::<Self>::current_at(route)
         };
+        // The lobby titles our window and tells us our name once it
+        // hears we're listening, since it may have renamed us.
+        let _lobby_listener = window.listen(ctx.link().clone());
+        tell_lobby(ctx, &crate::lobby::Remote::Ready(id));
+        let _geometry_tracker = (!inline)
+            .then(|| geometry::track(route.to_path()))
+            .flatten();
+
+        let _unload_listener =
+            (!inline && mode::platform() == Platform::Web && mode::mode() == Mode::MultiWindow)
+                .then(|| {
+                    use crate::Window;
+
+                    let window = gloo_utils::window();
+                    gloo_events::EventListener::new(&window, "beforeunload", move |_| {
+                        if let Ok(parent) = Window::<crate::Lobby>::opener()
+                            && let Err(e) = parent.send(&crate::lobby::Remote::CloseWindow(id))
+                        {
+                            log::error!("Could not send CloseWindow({id}): {e}");
+                        }
+                    })
+                });
 
         Table {
             state: format!("Created at {}", now.format("%H:%M:%S%.3f %Z")),
-
-            #[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
+            meta: TableMeta::new(id),
+            announcements: Vec::new(),
+            closing: false,
             _unload_listener,
+            _lobby_listener,
+            _geometry_tracker,
+            _title_watch: mode::is_spa()
+                .then(|| watch_titles(ctx.link().callback(|()| Msg::TitlesChanged))),
         }
     }
 
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        use Msg::*;
+
+        match msg {
+            Announce(announcement) => self.announcements.push(announcement),
+            Closing => self.closing = true,
+            ChangeGame(game) => self.update_meta(ctx, |meta| meta.game = game),
+            ChangeStatus(status) => self.update_meta(ctx, |meta| meta.status = status),
+            SitDown => self.update_meta(ctx, |meta| meta.participants += 1),
+            StandUp => self.update_meta(ctx, |meta| {
+                meta.participants = meta.participants.saturating_sub(1)
+            }),
+            // The lobby has already retitled our window
+            Rename(name) => self.meta.name = name,
+            TitlesChanged => {}
+        }
+        true
+    }
+
     fn view(&self, ctx: &Context<Self>) -> Html {
         let class = ctx.props().class();
         let nav_buttons = nav_buttons(ctx);
+        let id = ctx
+            .props()
+            .inline
+            .then(|| Route::Table { id: ctx.props().id }.to_path());
         html! {
-            <div {class}>
+            <div {class} {id}>
+                <h1 class="table-header">{ &self.meta.name }</h1>
                 { &self.state }
+                { self.meta_view(ctx) }
+                if self.closing {
+                    <p>{ "This table is being closed." }</p>
+                }
+                <ul class="announcements"> {
+                    for self.announcements.iter().map(|a| html! { <li>{ a }</li> })
+                } </ul>
                 { nav_buttons }
             </div>
         }
@@ -0,0 +1,124 @@
+use {
+    crate::{Tables, util::session_storage},
+    std::cell::Cell,
+    tauri_command_types::TableId,
+};
+
+// Kept in sessionStorage so that a reloaded lobby doesn't hand out the
+// ids of windows it has forgotten about but that are still open.
+const NEXT_KEY: &str = "spa-next-table-id";
+
+thread_local! {
+    static NEXT: Cell<TableId> = Cell::new(load());
+}
+
+fn load() -> TableId {
+    session_storage()
+        .and_then(|storage| storage.get_item(NEXT_KEY).ok().flatten())
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(TableId::FIRST)
+}
+
+fn set_next(id: TableId) {
+    NEXT.set(id);
+    if let Some(storage) = session_storage()
+        && let Err(e) = storage.set_item(NEXT_KEY, &id.to_string())
+    {
+        log::error!("could not store next table id: {}", crate::human(e));
+    }
+}
+
+/// An id for a new table, never one that's in `tables`.
+pub(crate) fn allocate(tables: &Tables) -> TableId {
+    let id = first_free(NEXT.get(), |id| tables.contains(id));
+    set_next(id.next());
+    id
+}
+
+/// Keeps `allocate` from handing out `id`, which is in use because of a
+/// link or a window that outlived the lobby.
+pub(crate) fn reserve(id: TableId) {
+    let next = NEXT.get();
+    let after = next_after(next, id);
+    if after != next {
+        set_next(after);
+    }
+}
+
+/// The first id from `next` on that isn't `in_use`.
+fn first_free(next: TableId, in_use: impl Fn(TableId) -> bool) -> TableId {
+    let mut id = next;
+    while in_use(id) {
+        id = id.next();
+    }
+    id
+}
+
+/// Where to go on allocating from once `reserved` is taken.
+fn next_after(next: TableId, reserved: TableId) -> TableId {
+    if reserved >= next {
+        reserved.next()
+    } else {
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u32) -> TableId {
+        n.to_string().parse().unwrap()
+    }
+
+    fn in_use(ids: &[u32]) -> impl Fn(TableId) -> bool {
+        move |candidate| ids.iter().any(|&n| id(n) == candidate)
+    }
+
+    #[test]
+    fn takes_next_when_free() {
+        assert_eq!(first_free(id(4), in_use(&[1, 2, 3])), id(4));
+    }
+
+    #[test]
+    fn skips_ids_in_use() {
+        assert_eq!(first_free(id(0), in_use(&[0, 1, 3])), id(2));
+    }
+
+    #[test]
+    fn reserving_below_next_changes_nothing() {
+        assert_eq!(next_after(id(5), id(3)), id(5));
+    }
+
+    #[test]
+    fn reserving_next_moves_past_it() {
+        assert_eq!(next_after(id(5), id(5)), id(6));
+    }
+
+    #[test]
+    fn reserving_above_next_moves_past_it() {
+        let next = next_after(id(2), id(7));
+        assert_eq!(next, id(8));
+        // 7 is never handed out, even once 8 is taken too
+        assert_eq!(first_free(next, in_use(&[7, 8])), id(9));
+    }
+
+    // What allocate and reserve do, without the sessionStorage.
+    #[test]
+    fn never_hands_out_an_open_id() {
+        let mut open = vec![];
+        let mut next = TableId::FIRST;
+        // A window that outlived the lobby, then a link
+        for reserved in [id(5), id(2)] {
+            next = next_after(next, reserved);
+            open.push(reserved);
+        }
+        for _ in 0..3 {
+            let new = first_free(next, |candidate| open.contains(&candidate));
+            assert!(!open.contains(&new));
+            open.push(new);
+            next = new.next();
+        }
+        assert_eq!(open, [id(5), id(2), id(6), id(7), id(8)]);
+    }
+}
@@ -1,31 +1,94 @@
 use {
-    crate::{Lobby, TableId, Window, lobby::Msg},
+    crate::{
+        Lobby, Route, Table, TableId, Window, WindowBackend,
+        lobby::Msg,
+        table,
+        table_meta::TableMeta,
+        util::utc_now,
+        window::{Subscription, subscribe},
+    },
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    std::{
+        cell::{Ref, RefCell, RefMut},
+        cmp::Ordering,
+        rc::Rc,
+    },
+    tauri_command_types::{Geometry, MenuTable, WindowEvent},
     yew::{html::Scope, prelude::*},
+    yew_router::Routable,
 };
 
-#[cfg(feature = "spa")]
-use crate::Route;
-
-pub(crate) type TableWindow = Window<Lobby>;
+pub(crate) type TableWindow = Window<Table>;
 
 pub(crate) struct TableInfo {
     pub(crate) id: TableId,
     pub(crate) window: TableWindow,
     pub(crate) close_callback: Callback<MouseEvent>,
+    pub(crate) focus_callback: Callback<MouseEvent>,
+    pub(crate) focused: bool,
+    pub(crate) minimized: bool,
+    /// As last asked for or reported, for placing new windows.
+    pub(crate) geometry: Geometry,
+    pub(crate) meta: TableMeta,
+    /// When the lobby found out about the table.
+    pub(crate) created: DateTime<Utc>,
+    /// When the table last changed what it says about itself or was
+    /// brought forward.
+    pub(crate) active: DateTime<Utc>,
+    _events: Subscription,
 }
 
 impl TableInfo {
-    pub(crate) fn new(link: &Scope<Lobby>, window: TableWindow, id: TableId) -> Self {
-        let link = link.clone();
-        let close_callback = Callback::from(move |_| link.send_message(Msg::CloseWindow(id)));
+    pub(crate) fn new(
+        link: &Scope<Lobby>,
+        window: TableWindow,
+        id: TableId,
+        geometry: Geometry,
+    ) -> Self {
+        let close_callback = {
+            let link = link.clone();
+            Callback::from(move |_| link.send_message(Msg::CloseWindow(id)))
+        };
+        let focus_callback = {
+            let link = link.clone();
+            Callback::from(move |_| link.send_message(Msg::FocusWindow(id)))
+        };
+        let _events = subscribe(
+            window.events(),
+            link.callback(move |event| Msg::TableWindowEvent(id, event)),
+        );
         Self {
             id,
             window,
             close_callback,
+            focus_callback,
+            focused: false,
+            minimized: false,
+            geometry,
+            meta: TableMeta::new(id),
+            created: utc_now(),
+            active: utc_now(),
+            _events,
+        }
+    }
+
+    fn apply(&mut self, event: WindowEvent) {
+        use WindowEvent::*;
+
+        match event {
+            Focused => {
+                self.focused = true;
+                self.active = utc_now();
+            }
+            Blurred => self.focused = false,
+            Minimized => self.minimized = true,
+            Restored => self.minimized = false,
+            Moved(position) => self.geometry.position = Some(position),
+            Resized(size) => self.geometry.size = Some(size),
         }
     }
 
-    #[cfg(feature = "spa")]
     pub(crate) fn left_this_right(
         tables: &[TableInfo],
     ) -> impl Iterator<Item = (Option<Route>, TableId, Option<Route>)> {
@@ -38,17 +101,88 @@ impl TableInfo {
         })
     }
 }
-#[cfg(feature = "spa")]
-use std::{
-    cell::{Ref, RefCell, RefMut},
-    rc::Rc,
-};
 
-#[cfg(feature = "spa")]
+/// A column of the lobby's list of tables.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) enum Column {
+    #[default]
+    Id,
+    Name,
+    Game,
+    Created,
+    Activity,
+    Status,
+    Participants,
+}
+
+impl Column {
+    pub(crate) const ALL: [Self; 7] = [
+        Self::Id,
+        Self::Name,
+        Self::Game,
+        Self::Created,
+        Self::Activity,
+        Self::Status,
+        Self::Participants,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::Id => "#",
+            Self::Name => "Name",
+            Self::Game => "Game",
+            Self::Created => "Created",
+            Self::Activity => "Last Activity",
+            Self::Status => "Status",
+            Self::Participants => "Players",
+        }
+    }
+
+    // Ties go by id, so that the order doesn't jump around.
+    fn compare(self, a: &TableInfo, b: &TableInfo) -> Ordering {
+        match self {
+            Self::Id => Ordering::Equal,
+            Self::Name => a.meta.name.cmp(&b.meta.name),
+            Self::Game => a.meta.game.cmp(&b.meta.game),
+            Self::Created => a.created.cmp(&b.created),
+            Self::Activity => a.active.cmp(&b.active),
+            Self::Status => a.meta.status.cmp(&b.meta.status),
+            Self::Participants => a.meta.participants.cmp(&b.meta.participants),
+        }
+        .then(a.id.cmp(&b.id))
+    }
+}
+
+/// How the lobby orders its list of tables.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct Sort {
+    pub(crate) column: Column,
+    pub(crate) descending: bool,
+}
+
+impl Sort {
+    /// What clicking on `column`'s heading does: reverses the order if
+    /// we're already sorted by it.
+    pub(crate) fn by(self, column: Column) -> Self {
+        Self {
+            column,
+            descending: column == self.column && !self.descending,
+        }
+    }
+
+    fn compare(self, a: &TableInfo, b: &TableInfo) -> Ordering {
+        let ordering = self.column.compare(a, b);
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct Tables(Rc<RefCell<Vec<TableInfo>>>);
 
-#[cfg(feature = "spa")]
 impl PartialEq for Tables {
     fn eq(&self, other: &Tables) -> bool {
         let s = self.0.borrow();
@@ -57,20 +191,24 @@ impl PartialEq for Tables {
     }
 }
 
-#[cfg(not(feature = "spa"))]
-#[derive(Default)]
-pub struct Tables(Vec<TableInfo>);
-
-// This is how we make remove_by_id use &self for spa and &mut self for non-spa
-macro_rules! remove_by_id {
-    ($self:ident, $id:ident) => {{
-        #[allow(unused_mut)]
-        let mut tables = $self.tables_mut();
+impl Tables {
+    pub(crate) fn remove_by_id(&self, id: TableId) -> bool {
+        // The borrow must end before we talk to the window, since in
+        // spa mode the table hears about it immediately.
+        let removed = {
+            let mut tables = self.tables_mut();
+            tables
+                .iter()
+                .position(|e| e.id == id)
+                .map(|i| tables.remove(i))
+        };
 
-        match tables.iter().position(|e| e.id == $id) {
-            Some(i) => {
+        match removed {
+            Some(TableInfo { window, .. }) => {
+                // The table may already be gone, so this is best effort.
+                let _ = window.send(&table::Remote::Closing);
                 // ignores the possibility of failure.
-                let _ = tables.remove(i).window.close();
+                let _ = window.close();
                 true
             }
             None => {
@@ -81,63 +219,190 @@ macro_rules! remove_by_id {
                 false
             }
         }
-    }};
-}
+    }
 
-impl Tables {
-    #[cfg(feature = "spa")]
-    pub(crate) fn remove_by_id(&self, id: TableId) -> bool {
-        remove_by_id!(self, id)
+    /// Records `event` against table `id`, returning whether anything
+    /// shown in the lobby changed.
+    pub(crate) fn apply_event(&self, id: TableId, event: WindowEvent) -> bool {
+        match self.tables_mut().iter_mut().find(|e| e.id == id) {
+            None => false,
+            Some(info) => {
+                let before = (info.focused, info.minimized, info.active);
+                info.apply(event);
+                before != (info.focused, info.minimized, info.active)
+            }
+        }
     }
 
-    #[cfg(not(feature = "spa"))]
-    pub(crate) fn remove_by_id(&mut self, id: TableId) -> bool {
-        remove_by_id!(self, id)
+    /// Records what table `id` says about itself, returning whether
+    /// that changed anything.
+    pub(crate) fn set_meta(&self, id: TableId, meta: TableMeta) -> bool {
+        match self.tables_mut().iter_mut().find(|e| e.id == id) {
+            Some(info) if info.meta != meta => {
+                info.meta = meta;
+                info.active = utc_now();
+                true
+            }
+            _ => false,
+        }
     }
 
-    #[cfg(feature = "spa")]
-    fn tables_mut(&self) -> RefMut<'_, Vec<TableInfo>> {
-        self.0.borrow_mut()
+    pub(crate) fn meta(&self, id: TableId) -> Option<TableMeta> {
+        self.tables()
+            .iter()
+            .find(|e| e.id == id)
+            .map(|info| info.meta.clone())
     }
 
-    #[cfg(not(feature = "spa"))]
-    fn tables_mut(&mut self) -> &mut Vec<TableInfo> {
-        &mut self.0
+    /// Renames table `id`, retitling its window and telling the table,
+    /// and returns whether there was such a table.
+    pub(crate) fn rename(&self, id: TableId, name: String) -> bool {
+        {
+            let mut tables = self.tables_mut();
+            let Some(info) = tables.iter_mut().find(|e| e.id == id) else {
+                return false;
+            };
+            info.meta.name = name;
+            info.active = utc_now();
+        }
+        self.retitle(id);
+        true
     }
 
-    #[cfg(feature = "spa")]
-    fn tables(&self) -> Ref<'_, Vec<TableInfo>> {
-        self.0.borrow()
+    /// Titles table `id`'s window with the table's name and tells the
+    /// table that name, which it doesn't know after (re)loading.
+    pub(crate) fn retitle(&self, id: TableId) {
+        // As in remove_by_id, the borrow ends before we talk to the window
+        let Some((window, name)) = self
+            .tables()
+            .iter()
+            .find(|e| e.id == id)
+            .map(|info| (info.window.clone(), info.meta.name.clone()))
+        else {
+            return;
+        };
+        window.set_title(name.clone());
+        if let Err(e) = window.send(&table::Remote::Rename(name)) {
+            log::warn!("Could not send to table {id}: {e}");
+        }
+    }
+
+    /// What to show in place of table `id` once it's gone: the table to
+    /// its right, or failing that, the one to its left.
+    pub(crate) fn neighbor(&self, id: TableId) -> Option<Route> {
+        TableInfo::left_this_right(&self.tables())
+            .find(|(_, this, _)| *this == id)
+            .and_then(|(left, _, right)| right.or(left))
+    }
+
+    /// The tables as the desktop app's Window menu lists them.
+    pub(crate) fn menu_tables(&self) -> Vec<MenuTable> {
+        self.tables()
+            .iter()
+            .map(|info| MenuTable {
+                id: info.id,
+                title: info.meta.name.clone(),
+            })
+            .collect()
+    }
+
+    /// The route of every table, in order.
+    pub(crate) fn paths(&self) -> Vec<String> {
+        self.tables()
+            .iter()
+            .map(|info| Route::Table { id: info.id }.to_path())
+            .collect()
+    }
+
+    pub(crate) fn contains(&self, id: TableId) -> bool {
+        self.tables().iter().any(|e| e.id == id)
+    }
+
+    pub(crate) fn focus_by_id(&self, id: TableId) {
+        if let Some(info) = self.tables().iter().find(|e| e.id == id)
+            && let Err(e) = info.window.focus()
+        {
+            log::error!("Could not focus table {id}: {e}");
+        }
+    }
+
+    /// The geometry of every table that's in a window of its own, oldest
+    /// first whatever order the lobby lists them in, since placement
+    /// works from the newest.
+    pub(crate) fn geometries(&self) -> Vec<Geometry> {
+        let tables = self.tables();
+        let mut windowed: Vec<_> = tables
+            .iter()
+            .filter(|info| !info.window.is_inline())
+            .collect();
+        windowed.sort_by_key(|info| (info.created, info.id));
+        windowed.into_iter().map(|info| info.geometry).collect()
+    }
+
+    fn tables_mut(&self) -> RefMut<'_, Vec<TableInfo>> {
+        self.0.borrow_mut()
     }
 
-    #[cfg(not(feature = "spa"))]
-    fn tables(&self) -> &[TableInfo] {
-        &self.0
+    fn tables(&self) -> Ref<'_, Vec<TableInfo>> {
+        self.0.borrow()
     }
 
-    #[cfg(feature = "spa")]
     pub(crate) fn push(&self, elem: TableInfo) {
         self.tables_mut().push(elem)
     }
 
-    #[cfg(not(feature = "spa"))]
-    pub(crate) fn push(&mut self, elem: TableInfo) {
-        self.tables_mut().push(elem)
+    /// Sends `msg` to table `id`, if it's still around.
+    pub(crate) fn send_to(&self, id: TableId, msg: &table::Remote) {
+        if let Some(info) = self.tables().iter().find(|e| e.id == id)
+            && let Err(e) = info.window.send(msg)
+        {
+            log::warn!("Could not send to table {id}: {e}");
+        }
+    }
+
+    /// Sends `msg` to every table, logging the ones we can't reach.
+    pub(crate) fn broadcast(&self, msg: &table::Remote) {
+        for TableInfo { id, window, .. } in self.tables().iter() {
+            if let Err(e) = window.send(msg) {
+                log::warn!("Could not send to table {id}: {e}");
+            }
+        }
+    }
+
+    /// Puts the tables in `sort`'s order, which is also the order
+    /// spa mode's left and right buttons go in.
+    pub(crate) fn sort(&self, sort: Sort) {
+        self.tables_mut().sort_by(|a, b| sort.compare(a, b));
+    }
+
+    /// Every table's id, in order.
+    pub(crate) fn ids(&self) -> Vec<TableId> {
+        self.tables().iter().map(|info| info.id).collect()
+    }
+
+    pub(crate) fn html(
+        &self,
+        keep: impl Fn(&TableInfo) -> bool,
+        f: impl Fn(&TableInfo) -> Html,
+    ) -> Html {
+        html! {
+            for self.tables().iter().filter(|info| keep(info)).map(f)
+        }
     }
 
-    pub(crate) fn html(&self, f: impl Fn(&TableInfo) -> Html) -> Html {
+    /// Renders the tables being shown within the lobby's page.
+    pub(crate) fn inline_html(&self, f: impl Fn(TableId) -> Html) -> Html {
         html! {
-            for self.tables().iter().map(f)
+            for self.tables().iter().filter(|info| info.window.is_inline()).map(|info| f(info.id))
         }
     }
 
-    #[cfg(feature = "spa")]
     pub(crate) fn triple_html(
         &self,
         f: impl Fn((Option<Route>, TableId, Option<Route>)) -> Html,
     ) -> Html {
         html! {
-            for TableInfo::left_this_right(&self.0.borrow()).map(f)
+            for TableInfo::left_this_right(&self.tables()).map(f)
         }
     }
 }
@@ -0,0 +1,62 @@
+use {
+    crate::{TableId, choice::impl_choice},
+    serde::{Deserialize, Serialize},
+};
+
+/// The game being played at a table.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub(crate) enum Game {
+    #[default]
+    Holdem,
+    Omaha,
+    Stud,
+    Razz,
+}
+
+impl_choice!(Game {
+    Holdem => ("holdem", "Hold'em"),
+    Omaha => ("omaha", "Omaha"),
+    Stud => ("stud", "Stud"),
+    Razz => ("razz", "Razz"),
+});
+
+/// How far along a table is.  Sorts in the order a table goes through
+/// them.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub(crate) enum Status {
+    #[default]
+    Waiting,
+    Playing,
+    Paused,
+    Finished,
+}
+
+impl_choice!(Status {
+    Waiting => ("waiting", "Waiting for Players"),
+    Playing => ("playing", "Playing"),
+    Paused => ("paused", "Paused"),
+    Finished => ("finished", "Finished"),
+});
+
+/// What the lobby shows about a table, kept up to date by the table
+/// itself.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct TableMeta {
+    pub(crate) name: String,
+    pub(crate) game: Game,
+    pub(crate) status: Status,
+    pub(crate) participants: u32,
+}
+
+impl TableMeta {
+    /// What a table starts out as, which is also what the lobby assumes
+    /// until the table says otherwise.
+    pub(crate) fn new(id: TableId) -> Self {
+        Self {
+            name: format!("Table {id}"),
+            game: Game::default(),
+            status: Status::default(),
+            participants: 0,
+        }
+    }
+}
@@ -13,6 +13,14 @@ pub(crate) fn utc_now() -> DateTime<Utc> {
     Utc.timestamp_opt(secs, nsecs).unwrap()
 }
 
+pub(crate) fn local_storage() -> Option<web_sys::Storage> {
+    gloo_utils::window().local_storage().ok().flatten()
+}
+
+pub(crate) fn session_storage() -> Option<web_sys::Storage> {
+    gloo_utils::window().session_storage().ok().flatten()
+}
+
 fn local_timezone() -> Option<String> {
     js_sys::Reflect::get(
         &DateTimeFormat::new0().resolved_options(),
@@ -1,13 +1,42 @@
 use {
-    crate::human, derive_more::Display, tauri_command_types::PopUpFeatures, wasm_bindgen::JsValue,
+    crate::{
+        Route, human,
+        mode::{self, Mode, Platform},
+    },
+    derive_more::Display,
+    futures::{
+        Stream, StreamExt,
+        future::{AbortHandle, Abortable},
+    },
+    gloo_events::EventListener,
+    serde::{Deserialize, Serialize},
+    tauri_command_types::{CommandError, PopUpFeatures, WindowEvent},
+    wasm_bindgen::JsValue,
+    yew::{Callback, Component, html::Scope},
+    yew_router::Routable,
 };
 
 #[derive(thiserror::Error, Debug, Display)]
 pub(crate) enum Error {
     Failure(String),
 
-    #[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
     NoWindow,
+
+    #[display("could not open window: {_0}")]
+    OpenFailed(CommandError),
+
+    NoChannel,
+
+    PostMessageFailed(String),
+
+    CantSerialize(serde_wasm_bindgen::Error),
+
+    CustomEventNewFailed(String),
+
+    DispatchEventFailed(String),
+
+    #[display("nothing is listening to {_0}")]
+    NoReceiver(String),
 }
 
 impl From<JsValue> for Error {
@@ -16,116 +45,321 @@ impl From<JsValue> for Error {
     }
 }
 
-#[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
+impl From<serde_wasm_bindgen::Error> for Error {
+    fn from(e: serde_wasm_bindgen::Error) -> Self {
+        Self::CantSerialize(e)
+    }
+}
+
 #[derive(thiserror::Error, Debug, Display)]
 pub(crate) enum OpenerError {
     Failure(String),
 
-    #[cfg(not(feature = "tauri"))]
     NoWindow,
 
-    #[cfg(not(feature = "tauri"))]
     #[display("wrong type: {_0:?}")]
     WrongType(JsValue),
-
-    #[cfg(feature = "tauri")]
-    BroadcastChannelFailure(String),
 }
 
-#[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
 impl From<JsValue> for OpenerError {
     fn from(jv: JsValue) -> Self {
         Self::Failure(human(jv))
     }
 }
 
-#[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
-#[derive(thiserror::Error, Debug, Display)]
-pub(crate) enum SendError {
-    #[cfg(feature = "tauri")]
-    NoChannel,
+/// A component that other windows can send messages to.  Only its
+/// `Remote` messages cross between windows, so its own `Message` never
+/// has to be serializable.
+pub(crate) trait Reachable: Component {
+    type Remote: Serialize + for<'a> Deserialize<'a>;
 
-    #[cfg(feature = "tauri")]
-    PostMessageFailed(String),
+    fn receive(remote: Self::Remote) -> Self::Message;
+}
 
-    CantSerialize(serde_wasm_bindgen::Error),
+/// The operations every `Window<C>` implementation has to provide,
+/// regardless of whether it's backed by a web_sys window, a Tauri
+/// WebviewWindow or a route in a single-page app.
+///
+/// `C` is the component that receives messages sent to the window.
+pub(crate) trait WindowBackend<C: Reachable>: Sized {
+    type Error: std::error::Error;
 
-    #[cfg(not(feature = "tauri"))]
-    CustomEventNewFailed(String),
+    /// Keeps the listener returned by `listen` alive; dropping it stops
+    /// delivery of messages.
+    type Listener;
 
-    #[cfg(not(feature = "tauri"))]
-    DispatchEventFailed(String),
+    /// Starts opening a window.  Anything that needs the user's gesture
+    /// (e.g. `window.open`) happens before this returns; the future
+    /// resolves once the window exists or has failed to appear.
+    fn open(
+        features: PopUpFeatures,
+        need_channel: bool,
+    ) -> impl Future<Output = Result<Self, Self::Error>> + 'static;
+
+    fn current() -> Self;
+
+    fn close(&self) -> Result<(), Self::Error>;
+
+    /// Brings the window to the front (in spa mode, shows its route).
+    fn focus(&self) -> Result<(), Self::Error>;
+
+    fn set_title(&self, title: String);
+
+    fn send(&self, message: &C::Remote) -> Result<(), Self::Error>;
+
+    fn listen(&self, link: Scope<C>) -> Self::Listener;
+
+    /// What happens to the window from now on.  Dropping the stream
+    /// stops listening.
+    fn events(&self) -> impl Stream<Item = WindowEvent> + 'static;
 }
 
-#[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
-impl From<serde_wasm_bindgen::Error> for SendError {
-    fn from(e: serde_wasm_bindgen::Error) -> Self {
-        Self::CantSerialize(e)
+/// The backend chosen at startup by `mode::platform()` and
+/// `mode::mode()`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Window<C: Component> {
+    Tauri(tauri::Window<C>),
+    Web(web_sys::Window<C>),
+    Spa(spa::Window<C>),
+}
+
+impl<C: Component> Window<C> {
+    /// The window that opened this one.  Under Tauri and in spa mode
+    /// every table is opened by the lobby.
+    pub(crate) fn opener() -> Result<Self, OpenerError> {
+        match (mode::platform(), mode::mode()) {
+            (_, Mode::SinglePage) => Ok(Self::Spa(spa::Window::with_label(Route::Index.to_path()))),
+            (Platform::Tauri, _) => Ok(Self::Tauri(tauri::Window::lobby())),
+            (Platform::Web, _) => web_sys::Window::opener().map(Self::Web),
+        }
+    }
+
+    /// A window that isn't one: `route` is shown inside the current page,
+    /// e.g. because a popup blocker stopped us from opening it.
+    pub(crate) fn inline(route: Route) -> Self {
+        Self::Spa(spa::Window::inline(route.to_path()))
+    }
+
+    /// A Tauri window that was opened before the lobby was (re)loaded.
+    pub(crate) fn reattach(label: String) -> Self {
+        Self::Tauri(tauri::Window::with_label(label))
+    }
+
+    pub(crate) fn is_inline(&self) -> bool {
+        matches!(self, Self::Spa(w) if w.is_inline())
+    }
+
+    /// The current window, which is showing `route`.  In spa mode every
+    /// route shares the one real window, so the route is what tells
+    /// them apart.
+    pub(crate) fn current_at(route: Route) -> Self
+    where
+        C: Reachable,
+    {
+        match (mode::platform(), mode::mode(), route) {
+            (_, Mode::SinglePage, _) => Self::Spa(spa::Window::with_label(route.to_path())),
+            (Platform::Tauri, Mode::MultiWindow, Route::Index) => {
+                Self::Tauri(tauri::Window::lobby())
+            }
+            (_, Mode::MultiWindow, _) => Self::current(),
+        }
+    }
+}
+
+impl<C: Reachable> WindowBackend<C> for Window<C> {
+    type Error = Error;
+    type Listener = Option<Listener>;
+
+    fn open(
+        features: PopUpFeatures,
+        need_channel: bool,
+    ) -> impl Future<Output = Result<Self, Error>> + 'static {
+        use futures::{FutureExt, TryFutureExt};
+
+        match (mode::platform(), mode::mode()) {
+            (_, Mode::SinglePage) => spa::Window::open(features, need_channel)
+                .map_ok(Self::Spa)
+                .boxed_local(),
+            (Platform::Tauri, _) => tauri::Window::open(features, need_channel)
+                .map_ok(Self::Tauri)
+                .boxed_local(),
+            (Platform::Web, _) => web_sys::Window::open(features, need_channel)
+                .map_ok(Self::Web)
+                .boxed_local(),
+        }
+    }
+
+    fn current() -> Self {
+        match (mode::platform(), mode::mode()) {
+            (_, Mode::SinglePage) => Self::Spa(spa::Window::current()),
+            (Platform::Tauri, _) => Self::Tauri(tauri::Window::current()),
+            (Platform::Web, _) => Self::Web(web_sys::Window::current()),
+        }
+    }
+
+    fn close(&self) -> Result<(), Error> {
+        match self {
+            Self::Tauri(w) => w.close(),
+            Self::Web(w) => w.close(),
+            Self::Spa(w) => w.close(),
+        }
+    }
+
+    fn focus(&self) -> Result<(), Error> {
+        match self {
+            Self::Tauri(w) => w.focus(),
+            Self::Web(w) => w.focus(),
+            Self::Spa(w) => w.focus(),
+        }
+    }
+
+    fn set_title(&self, title: String) {
+        match self {
+            Self::Tauri(w) => w.set_title(title),
+            Self::Web(w) => w.set_title(title),
+            Self::Spa(w) => w.set_title(title),
+        }
+    }
+
+    fn send(&self, message: &C::Remote) -> Result<(), Error> {
+        match self {
+            Self::Tauri(w) => w.send(message),
+            Self::Web(w) => w.send(message),
+            Self::Spa(w) => w.send(message),
+        }
+    }
+
+    fn listen(&self, link: Scope<C>) -> Self::Listener {
+        match self {
+            Self::Tauri(w) => w.listen(link).map(Listener::Event),
+            Self::Web(w) => Some(Listener::Event(w.listen(link))),
+            Self::Spa(w) => Some(Listener::Registration(w.listen(link))),
+        }
     }
+
+    fn events(&self) -> impl Stream<Item = WindowEvent> + 'static {
+        match self {
+            Self::Tauri(w) => w.events().boxed_local(),
+            Self::Web(w) => w.events().boxed_local(),
+            Self::Spa(w) => w.events().boxed_local(),
+        }
+    }
+}
+
+/// Stops the forwarding started by `subscribe` when dropped.
+pub(crate) struct Subscription(AbortHandle);
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Emits everything that comes out of `stream` to `callback`.
+pub(crate) fn subscribe<T: 'static>(
+    stream: impl Stream<Item = T> + 'static,
+    callback: Callback<T>,
+) -> Subscription {
+    let (handle, registration) = AbortHandle::new_pair();
+    yew::platform::spawn_local(Abortable::new(stream, registration).for_each(move |item| {
+        callback.emit(item);
+        std::future::ready(())
+    }));
+    Subscription(handle)
+}
+
+/// The events arriving on `receiver`, as sent by `listeners`, which are
+/// kept alive for as long as the stream itself is around.
+fn listener_stream<L: 'static>(
+    receiver: futures::channel::mpsc::UnboundedReceiver<WindowEvent>,
+    listeners: L,
+) -> impl Stream<Item = WindowEvent> + 'static {
+    receiver.map(move |event| {
+        let _ = &listeners;
+        event
+    })
+}
+
+pub(crate) use spa::{TitleWatch, set_title as set_spa_title, title as spa_title, watch_titles};
+
+/// Whatever a backend needs to keep alive to go on receiving messages.
+pub(crate) enum Listener {
+    Event(EventListener),
+    Registration(spa::Registration),
 }
 
-#[cfg(feature = "spa")]
-pub(crate) use spa::Window;
+const CUSTOM_EVENT_NAME: &str = "mb2-undocked-chat";
+
+fn dispatch<M: Serialize>(target: &::web_sys::EventTarget, message: &M) -> Result<(), Error> {
+    use {
+        ::web_sys::{CustomEvent, CustomEventInit},
+        Error::*,
+    };
 
-#[cfg(all(feature = "tauri", not(feature = "spa")))]
-pub(crate) use tauri::Window;
+    let request = serde_wasm_bindgen::to_value(message)?;
+    let init_dict = CustomEventInit::new();
+    init_dict.set_detail(&request);
+    let event = CustomEvent::new_with_event_init_dict(CUSTOM_EVENT_NAME, &init_dict)
+        .map_err(|e| CustomEventNewFailed(human(e)))?;
+    target
+        .dispatch_event(&event)
+        .map_err(|e| DispatchEventFailed(human(e)))
+        .map(|_| ())
+}
 
-#[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
-pub(crate) use web_sys::Window;
+fn custom_event_listener<C: Reachable>(
+    target: &::web_sys::EventTarget,
+    link: Scope<C>,
+) -> EventListener {
+    use {::web_sys::CustomEvent, wasm_bindgen::JsCast};
+
+    EventListener::new(target, CUSTOM_EVENT_NAME, move |e| {
+        // dyn_ref fails here, but unchecked_ref succeeds
+        let r = e.unchecked_ref::<CustomEvent>();
+        match serde_wasm_bindgen::from_value::<C::Remote>(r.detail()) {
+            Err(e) => log::warn!("Could not deserialize: {:?}", e),
+            Ok(r) => link.send_message(C::receive(r)),
+        }
+    })
+}
 
-#[cfg(all(feature = "tauri", not(feature = "spa")))]
 mod tauri {
     use {
-        super::{Error, PopUpFeatures, human},
+        super::{CommandError, Error, PopUpFeatures, Reachable, WindowBackend, human},
+        crate::Route,
+        futures::{Stream, StreamExt, future, stream},
+        gloo_events::EventListener,
         log::warn,
-        serde::{Deserialize, Serialize},
         std::marker::PhantomData,
-        web_sys::BroadcastChannel,
-        yew::Component,
+        tauri_command_types::{LOBBY, LabeledWindowEvent, WINDOW_EVENT, WindowEvent},
+        wasm_bindgen::JsCast,
+        web_sys::{BroadcastChannel, MessageEvent},
+        yew::{Component, html::Scope},
+        yew_router::Routable,
     };
 
     #[derive(Clone, Debug, Eq, PartialEq)]
     pub(crate) struct Window<C: Component> {
         label: String,
+        /// The path of the route the window shows, which names the
+        /// BroadcastChannel its messages go over.  A table's label is
+        /// its path, but the lobby's isn't.
+        path: String,
         message_type: PhantomData<C>,
         channel: Option<BroadcastChannel>,
     }
 
-    impl<C: Component> Window<C>
-    where
-        C::Message: Serialize + for<'a> Deserialize<'a>,
-    {
-        // NOTE: currently this code will always return Ok, even if tauri
-        // fails to open a new window.
-        #[cfg(not(feature = "spa"))]
-        pub(crate) fn new(features: PopUpFeatures, need_channel: bool) -> Result<Self, Error> {
-            let label = features.target.clone();
-            let channel = if need_channel {
-                match BroadcastChannel::new(&label) {
-                    Err(e) => {
-                        warn!("BroadcastChannel::new({label}) failed: {}", human(e));
-                        None
-                    }
-                    Ok(channel) => Some(channel),
-                }
-            } else {
+    fn new_channel(label: &str) -> Option<BroadcastChannel> {
+        match BroadcastChannel::new(label) {
+            Err(e) => {
+                warn!("BroadcastChannel::new({label}) failed: {}", human(e));
                 None
-            };
-            yew::platform::spawn_local(async move {
-                let _ = tauri_sys::core::invoke_result::<bool, String>("open_window", &features)
-                    .await
-                    // Ugh: although we log the error to the console, we
-                    // don't send a message to the calling component
-                    // saying that the open failed.
-                    .inspect_err(|e| log::error!("{e:?}"));
-            });
-            Ok(Self {
-                label,
-                message_type: PhantomData,
-                channel,
-            })
+            }
+            Ok(channel) => Some(channel),
         }
+    }
 
+    impl<C: Component> Window<C> {
         fn current_label() -> String {
             gloo_utils::window()
                 .location()
@@ -136,75 +370,168 @@ mod tauri {
                 })
         }
 
-        pub(crate) fn current() -> Self {
+        /// A window that already exists (or will soon), with a channel
+        /// for sending it messages.
+        pub(crate) fn with_label(label: String) -> Self {
+            let channel = new_channel(&label);
             Self {
-                label: Self::current_label(),
+                path: label.clone(),
+                label,
+                message_type: PhantomData,
+                channel,
+            }
+        }
+
+        /// The lobby's window, with a channel for sending it messages.
+        pub(crate) fn lobby() -> Self {
+            let path = Route::Index.to_path();
+            let channel = new_channel(&path);
+            Self {
+                label: LOBBY.to_string(),
+                path,
+                message_type: PhantomData,
+                channel,
+            }
+        }
+    }
+
+    impl<C: Reachable> WindowBackend<C> for Window<C> {
+        type Error = Error;
+        type Listener = Option<EventListener>;
+
+        async fn open(features: PopUpFeatures, need_channel: bool) -> Result<Self, Error> {
+            tauri_command_types::invoke(&features)
+                .await
+                .map_err(Error::OpenFailed)?;
+            let label = features.target;
+            let channel = if need_channel {
+                new_channel(&label)
+            } else {
+                None
+            };
+            Ok(Self {
+                path: label.clone(),
+                label,
+                message_type: PhantomData,
+                channel,
+            })
+        }
+
+        fn current() -> Self {
+            let label = Self::current_label();
+            Self {
+                path: label.clone(),
+                label,
                 message_type: PhantomData,
                 channel: None,
             }
         }
 
-        pub(crate) fn close(&self) -> Result<(), Error> {
+        fn close(&self) -> Result<(), Error> {
             let close = tauri_command_types::Close {
                 label: self.label.clone(),
             };
             yew::platform::spawn_local(async move {
-                if let Err(e) =
-                    tauri_sys::core::invoke_result::<bool, String>("close_window", &close).await
-                {
-                    log::error!("Could not inoke close_window: {e}");
+                match tauri_command_types::invoke(&close).await {
+                    // The user beat us to it
+                    Ok(()) | Err(CommandError::WindowNotFound(_)) => {}
+                    Err(e) => log::error!("Could not invoke close_window: {e}"),
+                }
+            });
+            Ok(())
+        }
+
+        fn focus(&self) -> Result<(), Error> {
+            let focus = tauri_command_types::Focus {
+                label: self.label.clone(),
+            };
+            yew::platform::spawn_local(async move {
+                match tauri_command_types::invoke(&focus).await {
+                    Ok(()) | Err(CommandError::Unsupported) => {}
+                    Err(CommandError::WindowNotFound(label)) => {
+                        log::warn!("{label} went away before it could be focused")
+                    }
+                    Err(e) => log::error!("could not invoke focus_window: {e}"),
                 }
             });
             Ok(())
         }
 
-        pub(crate) fn set_title(&self, title: String) {
+        fn set_title(&self, title: String) {
             let set_title = tauri_command_types::SetTitle {
                 label: self.label.clone(),
                 title,
             };
             yew::platform::spawn_local(async move {
-                if let Err(e) =
-                    tauri_sys::core::invoke_result::<bool, String>("set_title", &set_title).await
-                {
-                    log::error!("could not invoke set_title: {e}");
+                match tauri_command_types::invoke(&set_title).await {
+                    // There's nowhere to show a title
+                    Ok(()) | Err(CommandError::Unsupported) => {}
+                    Err(e) => log::error!("could not invoke set_title: {e}"),
                 }
             });
         }
+
+        fn send(&self, message: &C::Remote) -> Result<(), Error> {
+            let channel = self.channel.as_ref().ok_or(Error::NoChannel)?;
+            let message = serde_wasm_bindgen::to_value(message)?;
+            channel
+                .post_message(&message)
+                .map_err(|e| Error::PostMessageFailed(human(e)))
+        }
+
+        // A BroadcastChannel never receives its own messages, so we
+        // listen on a fresh one with the same name; the EventListener
+        // keeps it alive.
+        fn listen(&self, link: Scope<C>) -> Option<EventListener> {
+            let channel = new_channel(&self.path)?;
+            Some(EventListener::new(&channel, "message", move |e| {
+                let e = e.unchecked_ref::<MessageEvent>();
+                match serde_wasm_bindgen::from_value::<C::Remote>(e.data()) {
+                    Err(e) => log::warn!("Could not deserialize: {:?}", e),
+                    Ok(m) => link.send_message(C::receive(m)),
+                }
+            }))
+        }
+
+        // src-tauri emits WINDOW_EVENT for every window, so we pick out
+        // the ones for ours.
+        fn events(&self) -> impl Stream<Item = WindowEvent> + 'static {
+            let label = self.label.clone();
+            stream::once(tauri_sys::event::listen::<LabeledWindowEvent>(WINDOW_EVENT))
+                .filter_map(|events| {
+                    future::ready(
+                        events
+                            .inspect_err(|e| log::error!("Can't listen to {WINDOW_EVENT}: {e:?}"))
+                            .ok(),
+                    )
+                })
+                .flatten()
+                .filter_map(move |e| {
+                    future::ready((e.payload.label == label).then_some(e.payload.event))
+                })
+        }
     }
 }
 
-#[cfg(all(not(feature = "tauri"), not(feature = "spa")))]
 mod web_sys {
     use {
-        super::{Error, OpenerError, PopUpFeatures, SendError},
+        super::{Error, OpenerError, PopUpFeatures, Reachable, WindowBackend},
+        crate::geometry::inner_size,
+        futures::{Stream, channel::mpsc},
         gloo_events::EventListener,
-        serde::{Deserialize, Serialize},
         std::marker::PhantomData,
+        tauri_command_types::WindowEvent,
         wasm_bindgen::JsCast,
-        web_sys::{CustomEvent, CustomEventInit},
         yew::{Component, html::Scope},
     };
 
-    const CUSTOM_EVENT_NAME: &str = "mb2-undocked-chat";
-
     #[derive(Clone, Debug, Eq, PartialEq)]
     pub(crate) struct Window<C: Component> {
         window: web_sys::Window,
         message_type: PhantomData<C>,
     }
 
-    impl<C: Component> Window<C>
-    where
-        C::Message: Serialize + for<'a> Deserialize<'a>,
-    {
-        pub(crate) fn current() -> Self {
-            Self {
-                window: gloo_utils::window(),
-                message_type: PhantomData,
-            }
-        }
-
+    impl<C: Component> Window<C> {
         pub(crate) fn opener() -> Result<Self, OpenerError> {
             match gloo_utils::window().opener()? {
                 v if v.is_object() => Ok(Self {
@@ -215,95 +542,289 @@ mod web_sys {
                 v => Err(OpenerError::WrongType(v)),
             }
         }
+    }
 
-        #[cfg(not(feature = "spa"))]
-        pub(crate) fn new(features: PopUpFeatures, _need_channel: bool) -> Result<Self, Error> {
-            gloo_utils::window()
+    impl<C: Reachable> WindowBackend<C> for Window<C> {
+        type Error = Error;
+        type Listener = EventListener;
+
+        // Popup blockers only allow window.open during a user gesture,
+        // so the window is opened before the future is returned.
+        fn open(
+            features: PopUpFeatures,
+            _need_channel: bool,
+        ) -> impl Future<Output = Result<Self, Error>> + 'static {
+            let result = gloo_utils::window()
                 .open_with_url_and_target_and_features(
                     &features.url,
                     &features.target,
                     &features.to_string(),
-                )?
-                .ok_or(Error::NoWindow)
+                )
+                .map_err(Error::from)
+                .and_then(|window| window.ok_or(Error::NoWindow))
                 .map(|window| Self {
                     window,
                     message_type: PhantomData,
-                })
+                });
+            std::future::ready(result)
         }
 
-        pub(crate) fn set_title(&self, title: String) {
+        fn current() -> Self {
+            Self {
+                window: gloo_utils::window(),
+                message_type: PhantomData,
+            }
+        }
+
+        fn set_title(&self, title: String) {
             if let Some(document) = self.window.document() {
                 document.set_title(&title);
             }
         }
 
-        pub(crate) fn close(&self) -> Result<(), Error> {
+        fn close(&self) -> Result<(), Error> {
             self.window.close().map_err(Into::into)
         }
 
-        pub(crate) fn send(&self, message: &C::Message) -> Result<(), SendError> {
-            use super::{SendError::*, human};
+        fn focus(&self) -> Result<(), Error> {
+            self.window.focus().map_err(Into::into)
+        }
 
-            let request = serde_wasm_bindgen::to_value(message)?;
-            let init_dict = CustomEventInit::new();
-            init_dict.set_detail(&request);
-            let event = CustomEvent::new_with_event_init_dict(CUSTOM_EVENT_NAME, &init_dict)
-                .map_err(|e| CustomEventNewFailed(human(e)))?;
-            self.window
-                .dispatch_event(&event)
-                .map_err(|e| DispatchEventFailed(human(e)))
-                .map(|_| ())
+        fn send(&self, message: &C::Remote) -> Result<(), Error> {
+            super::dispatch(&self.window, message)
         }
 
-        pub(crate) fn listener(&self, link: Scope<C>) -> EventListener {
-            EventListener::new(&self.window, CUSTOM_EVENT_NAME, move |e| {
-                // dyn_ref fails here, but unchecked_ref succeeds
-                let r = e.unchecked_ref::<CustomEvent>();
-                match serde_wasm_bindgen::from_value::<C::Message>(r.detail()) {
-                    Err(e) => log::warn!("Could not deserialize: {:?}", e),
-                    Ok(r) => link.send_message(r),
-                }
-            })
+        fn listen(&self, link: Scope<C>) -> EventListener {
+            super::custom_event_listener(&self.window, link)
+        }
+
+        // Browsers don't tell us when a window moves.
+        fn events(&self) -> impl Stream<Item = WindowEvent> + 'static {
+            use WindowEvent::*;
+
+            let (sender, receiver) = mpsc::unbounded();
+            let on = |name: &'static str, event: fn(&web_sys::Window) -> Option<WindowEvent>| {
+                let sender = sender.clone();
+                let window = self.window.clone();
+                EventListener::new(&self.window, name, move |_| {
+                    if let Some(event) = event(&window) {
+                        let _ = sender.unbounded_send(event);
+                    }
+                })
+            };
+            let listeners = [
+                on("focus", |_| Some(Focused)),
+                on("blur", |_| Some(Blurred)),
+                on("resize", |w| inner_size(w).map(Resized)),
+                on("visibilitychange", |w| {
+                    w.document()
+                        .map(|d| if d.hidden() { Minimized } else { Restored })
+                }),
+            ];
+            super::listener_stream(receiver, listeners)
         }
     }
 }
 
-#[cfg(feature = "spa")]
 mod spa {
     use {
-        super::{Error, PopUpFeatures},
-        serde::{Deserialize, Serialize},
-        std::marker::PhantomData,
-        yew::Component,
+        super::{Error, PopUpFeatures, Reachable, WindowBackend},
+        futures::{Stream, channel::mpsc},
+        std::{
+            cell::{Cell, RefCell},
+            collections::HashMap,
+            marker::PhantomData,
+            rc::Rc,
+        },
+        tauri_command_types::WindowEvent,
+        wasm_bindgen::JsValue,
+        yew::{Callback, Component, html::Scope},
+        yew_router::history::{BrowserHistory, History},
     };
 
+    type Receiver = Rc<dyn Fn(JsValue)>;
+
+    thread_local! {
+        // Every "window" in spa mode lives in the same document, so
+        // messages are delivered by calling the receiver registered
+        // under the destination's label (its route's path).
+        static RECEIVERS: RefCell<HashMap<String, (u32, Receiver)>> = Default::default();
+        static NEXT_REGISTRATION: Cell<u32> = const { Cell::new(0) };
+
+        // There's no title bar per route, so titles are kept here for
+        // the app bar to show.
+        static TITLES: RefCell<HashMap<String, String>> = Default::default();
+        static TITLE_WATCHERS: RefCell<Vec<(u32, Callback<()>)>> = Default::default();
+    }
+
+    fn next_registration() -> u32 {
+        NEXT_REGISTRATION.replace(NEXT_REGISTRATION.get().wrapping_add(1))
+    }
+
+    pub(crate) fn title(label: &str) -> Option<String> {
+        TITLES.with_borrow(|titles| titles.get(label).cloned())
+    }
+
+    pub(crate) fn set_title(label: &str, title: String) {
+        TITLES.with_borrow_mut(|titles| titles.insert(label.to_string(), title));
+        // Cloned out, since a watcher may stop watching when told.
+        let watchers = TITLE_WATCHERS.with_borrow(|watchers| watchers.clone());
+        for (_, watcher) in watchers {
+            watcher.emit(());
+        }
+    }
+
+    /// Stops `watch_titles`' callback from being called when dropped.
+    pub(crate) struct TitleWatch(u32);
+
+    impl Drop for TitleWatch {
+        fn drop(&mut self) {
+            TITLE_WATCHERS.with_borrow_mut(|watchers| watchers.retain(|(id, _)| *id != self.0));
+        }
+    }
+
+    /// Calls `callback` whenever any title changes.
+    pub(crate) fn watch_titles(callback: Callback<()>) -> TitleWatch {
+        let id = next_registration();
+        TITLE_WATCHERS.with_borrow_mut(|watchers| watchers.push((id, callback)));
+        TitleWatch(id)
+    }
+
+    /// Unregisters its receiver when dropped.
+    pub(crate) struct Registration {
+        label: String,
+        id: u32,
+    }
+
+    impl Drop for Registration {
+        fn drop(&mut self) {
+            RECEIVERS.with_borrow_mut(|receivers| {
+                // A newer registration under the same label may have
+                // replaced ours, in which case it's not ours to remove.
+                if receivers
+                    .get(&self.label)
+                    .is_some_and(|(id, _)| *id == self.id)
+                {
+                    receivers.remove(&self.label);
+                }
+            });
+        }
+    }
+
     #[derive(Clone, Debug, Eq, PartialEq)]
     pub(crate) struct Window<C: Component> {
+        label: String,
+        inline: bool,
         message_type: PhantomData<C>,
     }
 
-    impl<C: Component> Window<C>
-    where
-        C::Message: Serialize + for<'a> Deserialize<'a>,
-    {
-        pub(crate) fn new(_features: PopUpFeatures, _need_channel: bool) -> Result<Self, Error> {
-            Ok(Self {
+    impl<C: Component> Window<C> {
+        pub(crate) fn with_label(label: String) -> Self {
+            Self {
+                label,
+                inline: false,
                 message_type: PhantomData,
-            })
+            }
         }
 
-        pub(crate) fn current() -> Self {
+        /// A table shown within the lobby's page in multi-window mode,
+        /// whose element has `label` as its id.
+        pub(crate) fn inline(label: String) -> Self {
             Self {
-                message_type: PhantomData,
+                inline: true,
+                ..Self::with_label(label)
             }
         }
 
-        pub(crate) fn set_title(&self, _title: String) {
-            // Title setting is not currently allowed in spa
+        pub(crate) fn is_inline(&self) -> bool {
+            self.inline
+        }
+    }
+
+    impl<C: Reachable> WindowBackend<C> for Window<C> {
+        type Error = Error;
+        type Listener = Registration;
+
+        async fn open(features: PopUpFeatures, _need_channel: bool) -> Result<Self, Error> {
+            Ok(Self::with_label(features.target))
+        }
+
+        fn current() -> Self {
+            Self::with_label(
+                gloo_utils::window()
+                    .location()
+                    .pathname()
+                    .unwrap_or_default(),
+            )
         }
 
-        pub(crate) fn close(&self) -> Result<(), Error> {
+        fn set_title(&self, title: String) {
+            set_title(&self.label, title);
+        }
+
+        fn close(&self) -> Result<(), Error> {
             Ok(()) // I don't think we need to do anything here
         }
+
+        fn focus(&self) -> Result<(), Error> {
+            if self.inline {
+                if let Some(element) = gloo_utils::document().get_element_by_id(&self.label) {
+                    element.scroll_into_view();
+                }
+            } else {
+                // This is the same history the app's BrowserRouter uses,
+                // so the router sees the change.
+                BrowserHistory::new().replace(self.label.clone());
+            }
+            Ok(())
+        }
+
+        fn send(&self, message: &C::Remote) -> Result<(), Error> {
+            let message = serde_wasm_bindgen::to_value(message)?;
+            // Clone the receiver out so that it's free to send messages
+            // (or drop registrations) of its own.
+            let receiver = RECEIVERS
+                .with_borrow(|receivers| receivers.get(&self.label).map(|(_, r)| r.clone()))
+                .ok_or_else(|| Error::NoReceiver(self.label.clone()))?;
+            receiver(message);
+            Ok(())
+        }
+
+        fn listen(&self, link: Scope<C>) -> Registration {
+            let id = next_registration();
+            let receiver: Receiver = Rc::new(move |message| {
+                match serde_wasm_bindgen::from_value::<C::Remote>(message) {
+                    Err(e) => log::warn!("Could not deserialize: {:?}", e),
+                    Ok(m) => link.send_message(C::receive(m)),
+                }
+            });
+            RECEIVERS.with_borrow_mut(|receivers| {
+                receivers.insert(self.label.clone(), (id, receiver));
+            });
+            Registration {
+                label: self.label.clone(),
+                id,
+            }
+        }
+
+        // A route is "focused" while it's the one being shown.
+        fn events(&self) -> impl Stream<Item = WindowEvent> + 'static {
+            let (sender, receiver) = mpsc::unbounded();
+            let history = BrowserHistory::new();
+            let label = self.label.clone();
+            let showing = Cell::new(history.location().path() == label);
+            let listener = history.clone().listen(move || {
+                let now_showing = history.location().path() == label;
+                if showing.replace(now_showing) != now_showing {
+                    let event = if now_showing {
+                        WindowEvent::Focused
+                    } else {
+                        WindowEvent::Blurred
+                    };
+                    let _ = sender.unbounded_send(event);
+                }
+            });
+            super::listener_stream(receiver, listener)
+        }
     }
 }
@@ -1,38 +1,152 @@
-use serde::{Deserialize, Serialize};
+use {
+    serde::{Deserialize, Serialize, de::DeserializeOwned},
+    std::{
+        fmt::{self, Display, Formatter},
+        num::ParseIntError,
+        str::FromStr,
+    },
+};
 
-#[cfg(not(feature = "spa"))]
-use std::fmt::{self, Display, Formatter};
+/// A Tauri command, identified by the struct holding its arguments.
+///
+/// Each handler in src-tauri is an `async fn` named `NAME` that takes
+/// the struct as a single parameter named `args` and returns
+/// `Response<Self>`.
+pub trait Command: Serialize + DeserializeOwned {
+    const NAME: &'static str;
+    type Ok: Serialize + DeserializeOwned;
+}
 
-#[derive(Debug, Deserialize, Serialize)]
+pub type Response<C> = Result<<C as Command>::Ok, CommandError>;
+
+/// Why a `Command` failed.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CommandError {
+    /// There's no window with this label.
+    WindowNotFound(String),
+    InvalidUrl {
+        url: String,
+        reason: String,
+    },
+    /// The url isn't one of the app's own pages.
+    ForeignOrigin(String),
+    BuildFailed(String),
+    /// The platform can't do this, e.g. Tauri Mobile has no window
+    /// titles.
+    Unsupported,
+    /// The window exists, but the operation on it failed.
+    Failed {
+        label: String,
+        reason: String,
+    },
+    MenuFailed(String),
+    /// The calling window isn't allowed to do this to window `label`.
+    Forbidden {
+        caller: String,
+        label: String,
+    },
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        use CommandError::*;
+
+        match self {
+            WindowNotFound(label) => write!(f, "no window {label}"),
+            InvalidUrl { url, reason } => write!(f, "invalid url {url}: {reason}"),
+            ForeignOrigin(url) => write!(f, "not one of our urls: {url}"),
+            BuildFailed(reason) => write!(f, "could not build window: {reason}"),
+            Unsupported => write!(f, "not supported on this platform"),
+            Failed { label, reason } => write!(f, "window {label}: {reason}"),
+            MenuFailed(reason) => write!(f, "could not update menu: {reason}"),
+            Forbidden { caller, label } => write!(f, "{caller} may not act on {label}"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+macro_rules! commands {
+    ($($name:ident($args:ty) -> $ok:ty;)*) => {
+        $(
+            impl Command for $args {
+                const NAME: &'static str = stringify!($name);
+                type Ok = $ok;
+            }
+        )*
+
+        /// For src-tauri's build script, which turns each into an
+        /// `allow-` and a `deny-` permission for capabilities.
+        pub const COMMAND_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+        /// `tauri::generate_handler!` for every command, so a missing
+        /// or misnamed handler won't compile.
+        #[macro_export]
+        macro_rules! generate_handler {
+            () => {
+                ::tauri::generate_handler![$($name),*]
+            };
+        }
+    };
+}
+
+commands! {
+    open_window(PopUpFeatures) -> ();
+    set_title(SetTitle) -> ();
+    close_window(Close) -> ();
+    focus_window(Focus) -> ();
+    take_deep_link(TakeDeepLink) -> Option<String>;
+    set_menu_tables(SetMenuTables) -> ();
+    list_windows(ListWindows) -> Vec<RegisteredWindow>;
+}
+
+/// The frontend's side of a `Command`.
+#[cfg(feature = "invoke")]
+pub async fn invoke<C: Command>(args: &C) -> Response<C> {
+    #[derive(Serialize)]
+    struct Args<'a, C> {
+        args: &'a C,
+    }
+
+    tauri_sys::core::invoke_result::<C::Ok, CommandError>(C::NAME, &Args { args }).await
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Size {
     pub height: u32,
     pub width: u32,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+// Signed, since windows on a secondary monitor can be above or to the
+// left of the primary one.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Position {
-    pub top: u32,
-    pub left: u32,
+    pub top: i32,
+    pub left: i32,
 }
 
-#[cfg(not(feature = "spa"))]
-#[derive(Debug, Deserialize, Serialize)]
-pub struct PopUpFeatures {
-    pub url: String,
-    pub target: String,
+/// A window's last known inner size and outer position, remembered
+/// between sessions.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Geometry {
     pub size: Option<Size>,
     pub position: Option<Position>,
-    #[cfg(not(feature = "spa"))]
-    pub close_notification: Option<CloseNotification>,
 }
 
-#[cfg(feature = "spa")]
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PopUpFeatures {
-    pub path: String,
+    pub url: String,
+    pub target: String,
+    /// The table the window shows.
+    pub id: TableId,
+    pub size: Option<Size>,
+    pub position: Option<Position>,
+    /// Whether the window's remembered geometry, if any, wins over
+    /// `size` and `position`.
+    pub restore_geometry: bool,
+    pub lifecycle: Option<LifecycleNotification>,
 }
 
-#[cfg(not(feature = "spa"))]
 impl Display for PopUpFeatures {
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
         let need_comma;
@@ -52,21 +166,147 @@ impl Display for PopUpFeatures {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Close {
     pub label: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
+pub struct Focus {
+    pub label: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SetTitle {
     pub label: String,
     pub title: String,
 }
 
+/// Which table a window shows.  It's part of the table's route and
+/// window label, so two tables that are open at once must never share
+/// one.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(transparent)]
+pub struct TableId(u32);
+
+impl TableId {
+    pub const FIRST: Self = Self(0);
+
+    /// The id after this one.  Wrapping around is left to whoever hands
+    /// out ids to skip the ones still in use.
+    pub fn next(self) -> Self {
+        Self(self.0.wrapping_add(1))
+    }
+}
+
+impl Display for TableId {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for TableId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+/// The label of the lobby's window, the one tauri.conf.json creates.
+pub static LOBBY: &str = "main";
+
+/// Asks src-tauri to emit `LIFECYCLE_EVENT`s about a table's window to
+/// the webview labeled `receiver_label`.
 #[derive(Debug, Deserialize, Serialize)]
-pub struct CloseNotification {
+pub struct LifecycleNotification {
     pub receiver_label: String,
-    pub id: u64, // Compromise
 }
 
-pub static CLOSED_EVENT: &str = "closed";
+/// The payload of `LIFECYCLE_EVENT`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum WindowLifecycleEvent {
+    Created(TableId),
+    Focused(TableId),
+    CloseRequested(TableId),
+    Closed(TableId),
+}
+
+pub static LIFECYCLE_EVENT: &str = "window-lifecycle";
+
+/// Asks for the path of the link the app was launched with, if any.
+/// Only the first asker gets it.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TakeDeepLink;
+
+/// Emitted to the lobby with the path (e.g. `/table/12`) of a link
+/// that was opened while the app was running.
+pub static DEEP_LINK_EVENT: &str = "deep-link";
+
+/// A table, as listed in the desktop app's Window menu.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MenuTable {
+    pub id: TableId,
+    pub title: String,
+}
+
+/// Rebuilds the menu to list `tables`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetMenuTables {
+    pub tables: Vec<MenuTable>,
+}
+
+/// The payload of `MENU_EVENT`: what the lobby should do because a
+/// menu item was chosen.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MenuAction {
+    NewTable,
+    CloseTable(TableId),
+}
+
+pub static MENU_EVENT: &str = "menu";
+
+/// Emitted with a `SetTitle` on mobile, where there's only one window
+/// and the single-page lobby shows titles itself.
+pub static SET_TITLE_EVENT: &str = "set-title";
+
+/// Emitted with a `Close` on mobile, where closing a table is up to the
+/// single-page lobby.
+pub static CLOSE_WINDOW_EVENT: &str = "close-window";
+
+/// Asks for every table window src-tauri has opened that's still open,
+/// so that a reloaded lobby can pick up where it left off.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListWindows;
+
+/// A table window, as src-tauri knows it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RegisteredWindow {
+    pub label: String,
+    pub id: TableId,
+    /// As last set by `SetTitle`.
+    pub title: String,
+    pub geometry: Geometry,
+}
+
+/// Something that happened to a window, as delivered by
+/// `Window::events` in the frontend.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum WindowEvent {
+    Moved(Position),
+    Resized(Size),
+    Focused,
+    Blurred,
+    Minimized,
+    Restored,
+}
+
+/// The payload of `WINDOW_EVENT`, which src-tauri emits for every
+/// window it manages.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LabeledWindowEvent {
+    pub label: String,
+    pub event: WindowEvent,
+}
+
+pub static WINDOW_EVENT: &str = "window-event";